@@ -0,0 +1,161 @@
+//! Command-line interface for loading, validating and describing dataframe configurations.
+
+#[macro_use] extern crate clap;
+extern crate error_chain;
+extern crate etl;
+extern crate serde_json;
+extern crate toml;
+
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::Path;
+use std::process;
+
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+use error_chain::ChainedError;
+
+use etl::dataframe::{CsvFormat, DataConfig, DataFrame, FieldType};
+
+fn main() {
+    let matches = App::new("etl")
+        .version(crate_version!())
+        .about("Extract, transform and load data as specified by a TOML or JSON configuration")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .subcommand(SubCommand::with_name("run")
+            .about("Load a configuration's dataframe and write it as CSV")
+            .arg(Arg::with_name("config").required(true).help("Configuration file"))
+            .arg(Arg::with_name("output").short("o").long("output").takes_value(true)
+                .value_name("FILE")
+                .help("Output CSV file (defaults to the configured output or standard output)")))
+        .subcommand(SubCommand::with_name("validate")
+            .about("Check that a configuration is valid and its source files exist")
+            .arg(Arg::with_name("config").required(true).help("Configuration file")))
+        .subcommand(SubCommand::with_name("infer")
+            .about("Generate a configuration from a sample of a delimited data file (to be saved \
+                in the same directory as the data file)")
+            .arg(Arg::with_name("data").required(true).help("Delimited data file"))
+            .arg(Arg::with_name("delimiter").short("d").long("delimiter").takes_value(true)
+                .value_name("CHAR").help("Delimiter of the data file (defaults to ',')"))
+            .arg(Arg::with_name("json").long("json")
+                .help("Generate a JSON configuration instead of TOML")))
+        .subcommand(SubCommand::with_name("describe")
+            .about("Load a configuration's dataframe and summarize its fields")
+            .arg(Arg::with_name("config").required(true).help("Configuration file")))
+        .get_matches();
+
+    let result = match matches.subcommand() {
+        ("run", Some(args))      => run(args),
+        ("validate", Some(args)) => validate(args),
+        ("infer", Some(args))    => infer(args),
+        ("describe", Some(args)) => describe(args),
+        _                        => unreachable!("subcommand is required"),
+    };
+    if let Err(message) = result {
+        eprint!("{}", message);
+        process::exit(1);
+    }
+}
+
+fn run(args: &ArgMatches) -> Result<(), String> {
+    let (config, df) = DataFrame::load(Path::new(args.value_of("config").unwrap()))
+        .map_err(display_chain)?;
+    match (args.value_of("output"), config.output) {
+        (Some(path), _) => {
+            let file = File::create(path)
+                .map_err(|e| format!("Error: unable to create {}: {}\n", path, e))?;
+            df.write_csv(BufWriter::new(file), &CsvFormat::default())
+        }
+        (None, Some(output)) => df.write_output(&output),
+        (None, None) => df.write_csv(io::stdout(), &CsvFormat::default()),
+    }.map_err(display_chain)
+}
+
+fn validate(args: &ArgMatches) -> Result<(), String> {
+    let path = args.value_of("config").unwrap();
+    let config = DataConfig::from_config(Path::new(path)).map_err(display_chain)?;
+    println!("{}: valid ({} source file(s), {} transform(s))", path, config.source_files.len(),
+        config.transforms.as_ref().map_or(0, |transforms| transforms.len()));
+    Ok(())
+}
+
+fn infer(args: &ArgMatches) -> Result<(), String> {
+    let delimiter = match args.value_of("delimiter") {
+        Some("\\t") => b'\t',
+        Some(delim) if delim.len() == 1 => delim.as_bytes()[0],
+        Some(delim) => { return Err(format!("Error: invalid delimiter: {}\n", delim)); }
+        None => b',',
+    };
+    let config = DataConfig::infer(Path::new(args.value_of("data").unwrap()), delimiter)
+        .map_err(display_chain)?;
+    let serialized = if args.is_present("json") {
+        serde_json::to_string_pretty(&config).map_err(|e| format!("Error: {}\n", e))?
+    } else {
+        toml::to_string(&config).map_err(|e| format!("Error: {}\n", e))?
+    };
+    println!("{}", serialized);
+    Ok(())
+}
+
+fn describe(args: &ArgMatches) -> Result<(), String> {
+    let (_, df) = DataFrame::load(Path::new(args.value_of("config").unwrap()))
+        .map_err(display_chain)?;
+    println!("{} rows, {} fields", df.nrows(), df.fieldnames().len());
+    println!("{:<24} {:<10} {:>8}  summary", "field", "type", "missing");
+    for field_name in df.fieldnames() {
+        let field_type = df.get_field_type(field_name).expect("field exists");
+        let mask = df.get_null_mask(field_name);
+        let summary = match field_type {
+            FieldType::Unsigned => range(df.get_unsigned_field(field_name).unwrap(), mask),
+            FieldType::Signed   => range(df.get_signed_field(field_name).unwrap(), mask),
+            FieldType::Float    => range(&df.get_float_values(field_name).unwrap(), mask),
+            FieldType::Date     => range(df.get_date_field(field_name).unwrap(), mask),
+            FieldType::DateTime => range(df.get_datetime_field(field_name).unwrap(), mask),
+            FieldType::Duration => range(&duration_seconds(&df, field_name), mask),
+            FieldType::Text     => distinct(df.get_text_field(field_name).unwrap(), mask),
+            FieldType::Boolean  => distinct(df.get_boolean_field(field_name).unwrap(), mask),
+        };
+        println!("{:<24} {:<10} {:>8}  {}", field_name, format!("{:?}", field_type),
+            df.null_count(field_name), summary);
+    }
+    Ok(())
+}
+
+/// Values of a duration field in seconds
+fn duration_seconds(df: &DataFrame, field_name: &str) -> Vec<f64> {
+    df.get_duration_field(field_name).unwrap().iter()
+        .map(|d| d.num_milliseconds() as f64 / 1000.0).collect()
+}
+
+/// Values of a field which are not missing
+fn present<T: Clone>(values: &[T], mask: Option<&Vec<bool>>) -> Vec<T> {
+    values.iter().enumerate().filter(|&(row, _)| !mask.is_some_and(|mask| mask[row]))
+        .map(|(_, value)| value.clone()).collect()
+}
+
+/// Number of distinct values of a field which are not missing
+fn distinct<T: Clone + Ord>(values: &[T], mask: Option<&Vec<bool>>) -> String {
+    let mut values = present(values, mask);
+    values.sort();
+    values.dedup();
+    format!("{} distinct", values.len())
+}
+
+/// Minimum and maximum of the values of a field which are not missing
+fn range<T: Clone + Display + PartialOrd>(values: &[T], mask: Option<&Vec<bool>>) -> String {
+    let values = present(values, mask);
+    let mut iter = values.iter();
+    match iter.next() {
+        Some(first) => {
+            let (min, max) = iter.fold((first, first), |(min, max), value| {
+                (if value < min { value } else { min }, if value > max { value } else { max })
+            });
+            format!("min {}, max {}", min, max)
+        }
+        None => "no values".to_string(),
+    }
+}
+
+fn display_chain<E: ChainedError>(err: E) -> String {
+    err.display_chain().to_string()
+}
@@ -0,0 +1,251 @@
+//! Parquet and Arrow IPC source reading and dataframe writing
+
+use std::io::{Cursor, Read, Write};
+use std::sync::Arc;
+
+use arrow_array::{Array, ArrayRef, ArrowPrimitiveType, BooleanArray, Date32Array,
+    DurationMicrosecondArray, Float64Array, Int64Array, PrimitiveArray, RecordBatch, StringArray,
+    TimestampMicrosecondArray, UInt64Array};
+use arrow_array::cast::AsArray;
+use arrow_array::types::{ArrowTemporalType, Date32Type, Date64Type, DurationMicrosecondType,
+    DurationMillisecondType, DurationNanosecondType, DurationSecondType,
+    TimestampMicrosecondType, TimestampMillisecondType, TimestampNanosecondType,
+    TimestampSecondType};
+use arrow_cast::cast;
+use arrow_ipc::reader::FileReader;
+use arrow_ipc::writer::FileWriter;
+use arrow_schema::{ArrowError, DataType, Field as ArrowField, Schema, TimeUnit};
+use bytes::Bytes;
+use csv::ByteRecord;
+use parquet::arrow::ArrowWriter;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+use dataframe::FieldType;
+use dataframe::config::Field;
+use dataframe::datastore::DataStore;
+use dataframe::temporal;
+
+use errors::*;
+
+type RecordBatches = Box<dyn Iterator<Item=::std::result::Result<RecordBatch, ArrowError>>>;
+
+/// Reader which extracts a record for each row of a columnar (Parquet or Arrow IPC) source, with
+/// one entry per field. Columns are found by the source name of each field. Values are written
+/// in the same form as in text sources (dates, date-times and durations in the field's format, or
+/// ISO 8601 and "HH:MM:SS" by default), and null values are stored as empty fields.
+pub struct ColumnarReader<'a> {
+    batches: RecordBatches,
+    fields: &'a [Field],
+    columns: Vec<Vec<String>>,
+    row: usize,
+}
+impl<'a> ColumnarReader<'a> {
+    /// Create a new reader for a Parquet source, extracting the specified fields
+    pub fn parquet<R: Read>(mut reader: R, fields: &'a [Field]) -> Result<ColumnarReader<'a>> {
+        let mut contents = vec![];
+        reader.read_to_end(&mut contents).chain_err(|| "error reading Parquet source")?;
+        let batches = ParquetRecordBatchReaderBuilder::try_new(Bytes::from(contents))
+            .and_then(|builder| builder.build())
+            .chain_err(|| "error reading Parquet source")?;
+        Ok(ColumnarReader::new(Box::new(batches), fields))
+    }
+
+    /// Create a new reader for an Arrow IPC file source, extracting the specified fields
+    pub fn arrow_ipc<R: Read>(mut reader: R, fields: &'a [Field]) -> Result<ColumnarReader<'a>> {
+        let mut contents = vec![];
+        reader.read_to_end(&mut contents).chain_err(|| "error reading Arrow IPC source")?;
+        let batches = FileReader::try_new(Cursor::new(contents), None)
+            .chain_err(|| "error reading Arrow IPC source")?;
+        Ok(ColumnarReader::new(Box::new(batches), fields))
+    }
+
+    fn new(batches: RecordBatches, fields: &'a [Field]) -> ColumnarReader<'a> {
+        ColumnarReader {
+            batches,
+            fields,
+            columns: vec![],
+            row: 0,
+        }
+    }
+
+    fn load_batch(&mut self, batch: &RecordBatch) -> Result<()> {
+        let mut columns = vec![];
+        for field in self.fields {
+            let array = batch.column_by_name(&field.source_name).ok_or_else(|| Error::from_kind(
+                ErrorKind::DataFrameError(format!("column {} not found in source",
+                    field.source_name))))?;
+            columns.push(column_strings(array, field.format())
+                .chain_err(|| format!("error reading column {}", field.source_name))?);
+        }
+        self.columns = columns;
+        self.row = 0;
+        Ok(())
+    }
+}
+impl<'a> Iterator for ColumnarReader<'a> {
+    type Item = Result<ByteRecord>;
+
+    fn next(&mut self) -> Option<Result<ByteRecord>> {
+        // load batches until one with remaining rows is found
+        while self.columns.first().is_none_or(|column| self.row >= column.len()) {
+            let batch = match self.batches.next()? {
+                Ok(batch) => batch,
+                Err(e) => { return Some(Err(e).chain_err(|| "error reading record batch")); }
+            };
+            if let Err(e) = self.load_batch(&batch) {
+                return Some(Err(e));
+            }
+            if self.fields.is_empty() {
+                return None;
+            }
+        }
+        let record = self.columns.iter().map(|column| &column[self.row]).collect();
+        self.row += 1;
+        Some(Ok(record))
+    }
+}
+
+/// Values of a column in string form, with null values as empty strings
+fn column_strings(array: &ArrayRef, format: Option<&str>) -> Result<Vec<String>> {
+    let values: Vec<Option<String>> = match *array.data_type() {
+        DataType::Date32 => temporal_values::<Date32Type, _>(array, |dates, i| {
+            dates.value_as_date(i).map(|d| temporal::format_date(&d, format))
+        }),
+        DataType::Date64 => temporal_values::<Date64Type, _>(array, |dates, i| {
+            dates.value_as_date(i).map(|d| temporal::format_date(&d, format))
+        }),
+        DataType::Timestamp(unit, _) => match unit {
+            TimeUnit::Second      => format_timestamps::<TimestampSecondType>(array, format),
+            TimeUnit::Millisecond => format_timestamps::<TimestampMillisecondType>(array, format),
+            TimeUnit::Microsecond => format_timestamps::<TimestampMicrosecondType>(array, format),
+            TimeUnit::Nanosecond  => format_timestamps::<TimestampNanosecondType>(array, format),
+        },
+        DataType::Duration(unit) => match unit {
+            TimeUnit::Second      => format_durations::<DurationSecondType>(array),
+            TimeUnit::Millisecond => format_durations::<DurationMillisecondType>(array),
+            TimeUnit::Microsecond => format_durations::<DurationMicrosecondType>(array),
+            TimeUnit::Nanosecond  => format_durations::<DurationNanosecondType>(array),
+        },
+        _ => {
+            let strings = cast(array, &DataType::Utf8).chain_err(|| format!(
+                "unsupported column type {}", array.data_type()))?;
+            strings.as_string::<i32>().iter().map(|s| s.map(|s| s.to_string())).collect()
+        }
+    };
+    Ok(values.into_iter().map(|value| value.unwrap_or_default()).collect())
+}
+
+/// Formatted values of a temporal column, with `None` for null values
+fn temporal_values<T, F>(array: &ArrayRef, format_value: F) -> Vec<Option<String>>
+        where T: ArrowPrimitiveType, F: Fn(&PrimitiveArray<T>, usize) -> Option<String> {
+    let values = array.as_primitive::<T>();
+    (0..values.len())
+        .map(|i| if values.is_null(i) { None } else { format_value(values, i) })
+        .collect()
+}
+
+fn format_timestamps<T>(array: &ArrayRef, format: Option<&str>) -> Vec<Option<String>>
+        where T: ArrowTemporalType, i64: From<T::Native> {
+    temporal_values::<T, _>(array, |datetimes, i| datetimes.value_as_datetime(i)
+        .map(|dt| temporal::format_datetime(&dt, format)))
+}
+
+fn format_durations<T>(array: &ArrayRef) -> Vec<Option<String>>
+        where T: ArrowTemporalType, i64: From<T::Native> {
+    temporal_values::<T, _>(array, |durations, i| durations.value_as_duration(i)
+        .map(|d| temporal::format_duration(&d)))
+}
+
+/// Arrow data type used to store fields of the specified field type. Parquet has no duration
+/// type, so durations are stored as (floating-point) seconds in Parquet files
+fn arrow_type(field_type: FieldType, durations_as_seconds: bool) -> DataType {
+    match field_type {
+        FieldType::Unsigned => DataType::UInt64,
+        FieldType::Signed   => DataType::Int64,
+        FieldType::Text     => DataType::Utf8,
+        FieldType::Boolean  => DataType::Boolean,
+        FieldType::Float    => DataType::Float64,
+        FieldType::Date     => DataType::Date32,
+        FieldType::DateTime => DataType::Timestamp(TimeUnit::Microsecond, None),
+        FieldType::Duration if durations_as_seconds => DataType::Float64,
+        FieldType::Duration => DataType::Duration(TimeUnit::Microsecond),
+    }
+}
+
+/// Convert all fields of a data store (in field order) into an Arrow record batch. Missing values
+/// are stored as nulls.
+fn record_batch(data: &DataStore, durations_as_seconds: bool) -> Result<RecordBatch> {
+    let mut schema_fields = vec![];
+    let mut columns: Vec<ArrayRef> = vec![];
+    for finfo in data.fields() {
+        let name = &finfo.name;
+        let mask = data.get_null_mask(name);
+        let column: ArrayRef = match finfo.ty {
+            FieldType::Unsigned => Arc::new(UInt64Array::from(optional(
+                data.get_unsigned_field(name).expect("datastore inconsistent"), mask))),
+            FieldType::Signed   => Arc::new(Int64Array::from(optional(
+                data.get_signed_field(name).expect("datastore inconsistent"), mask))),
+            FieldType::Text     => Arc::new(StringArray::from(optional(
+                data.get_text_field(name).expect("datastore inconsistent"), mask))),
+            FieldType::Boolean  => Arc::new(BooleanArray::from(optional(
+                data.get_boolean_field(name).expect("datastore inconsistent"), mask))),
+            FieldType::Float    => Arc::new(Float64Array::from(optional(
+                &data.get_float_values(name).expect("datastore inconsistent"), mask))),
+            FieldType::Date     => {
+                let days = data.get_date_field(name).expect("datastore inconsistent").iter()
+                    .map(|d| (*d - temporal::epoch_date()).num_days() as i32).collect::<Vec<_>>();
+                Arc::new(Date32Array::from(optional(&days, mask)))
+            }
+            FieldType::DateTime => {
+                let micros = data.get_datetime_field(name).expect("datastore inconsistent")
+                    .iter().map(|dt| dt.and_utc().timestamp_micros()).collect::<Vec<_>>();
+                Arc::new(TimestampMicrosecondArray::from(optional(&micros, mask)))
+            }
+            FieldType::Duration if durations_as_seconds => {
+                let seconds = data.get_duration_field(name).expect("datastore inconsistent")
+                    .iter().map(temporal::duration_to_seconds).collect::<Vec<_>>();
+                Arc::new(Float64Array::from(optional(&seconds, mask)))
+            }
+            FieldType::Duration => {
+                let micros = data.get_duration_field(name).expect("datastore inconsistent")
+                    .iter().map(|d| d.num_microseconds().ok_or_else(|| Error::from_kind(
+                        ErrorKind::DataFrameError(format!("duration out of range in field {}",
+                            name)))))
+                    .collect::<Result<Vec<_>>>()?;
+                Arc::new(DurationMicrosecondArray::from(optional(&micros, mask)))
+            }
+        };
+        schema_fields.push(ArrowField::new(name.clone(),
+            arrow_type(finfo.ty, durations_as_seconds), true));
+        columns.push(column);
+    }
+    RecordBatch::try_new(Arc::new(Schema::new(schema_fields)), columns)
+        .chain_err(|| "error converting dataframe to record batch")
+}
+
+/// Values of a field, with `None` for missing values
+fn optional<T: Clone>(values: &[T], mask: Option<&Vec<bool>>) -> Vec<Option<T>> {
+    values.iter().enumerate().map(|(row, value)| {
+        if mask.is_some_and(|mask| mask[row]) { None } else { Some(value.clone()) }
+    }).collect()
+}
+
+/// Write all fields of a data store (in field order) as a Parquet file
+pub fn write_parquet<W: Write + Send>(data: &DataStore, writer: W) -> Result<()> {
+    let batch = record_batch(data, true)?;
+    let mut writer = ArrowWriter::try_new(writer, batch.schema(), None)
+        .chain_err(|| "error writing Parquet file")?;
+    writer.write(&batch).chain_err(|| "error writing Parquet file")?;
+    writer.close().chain_err(|| "error writing Parquet file")?;
+    Ok(())
+}
+
+/// Write all fields of a data store (in field order) as an Arrow IPC file
+pub fn write_arrow_ipc<W: Write>(data: &DataStore, writer: W) -> Result<()> {
+    let batch = record_batch(data, false)?;
+    let mut writer = FileWriter::try_new(writer, &batch.schema())
+        .chain_err(|| "error writing Arrow IPC file")?;
+    writer.write(&batch).chain_err(|| "error writing Arrow IPC file")?;
+    writer.finish().chain_err(|| "error writing Arrow IPC file")?;
+    Ok(())
+}
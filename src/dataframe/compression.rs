@@ -0,0 +1,97 @@
+//! Compression codecs for source files
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+
+use flate2::read::MultiGzDecoder;
+use zstd::stream::read::Decoder as ZstdDecoder;
+use bzip2::read::MultiBzDecoder;
+
+use errors::*;
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+const BZIP2_MAGIC: &[u8] = b"BZh";
+
+/// Compression codec used for a source file
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Compression {
+    /// Uncompressed file
+    None,
+    /// Gzip-compressed file
+    Gzip,
+    /// Zstandard-compressed file
+    Zstd,
+    /// Bzip2-compressed file
+    Bzip2,
+}
+
+impl Compression {
+    /// Parse a codec name as specified in a configuration file
+    pub fn from_name(name: &str) -> Result<Compression> {
+        match &name.to_lowercase()[..] {
+            "none"          => Ok(Compression::None),
+            "gzip" | "gz"   => Ok(Compression::Gzip),
+            "zstd" | "zst"  => Ok(Compression::Zstd),
+            "bzip2" | "bz2" => Ok(Compression::Bzip2),
+            _               => Err(Error::from_kind(ErrorKind::DataConfigError(
+                format!("unsupported compression codec: {}", name)))),
+        }
+    }
+
+    /// Guess the codec from the extension of a file path, if the extension is a known one
+    pub fn from_extension(path: &Path) -> Option<Compression> {
+        match path.extension().and_then(|ext| ext.to_str()).map(|ext| ext.to_lowercase()) {
+            Some(ref ext) if ext == "gz"  => Some(Compression::Gzip),
+            Some(ref ext) if ext == "zst" => Some(Compression::Zstd),
+            Some(ref ext) if ext == "bz2" => Some(Compression::Bzip2),
+            _                             => None,
+        }
+    }
+
+    /// Guess the codec from the leading bytes of a file's contents
+    pub fn from_magic(bytes: &[u8]) -> Compression {
+        if bytes.starts_with(GZIP_MAGIC) {
+            Compression::Gzip
+        } else if bytes.starts_with(ZSTD_MAGIC) {
+            Compression::Zstd
+        } else if bytes.starts_with(BZIP2_MAGIC) {
+            Compression::Bzip2
+        } else {
+            Compression::None
+        }
+    }
+
+    /// Wrap a reader with a stream decoder for this codec
+    pub fn decoder<'a, R: BufRead + 'a>(&self, reader: R) -> Result<Box<dyn Read + 'a>> {
+        Ok(match *self {
+            Compression::None  => Box::new(reader),
+            Compression::Gzip  => Box::new(MultiGzDecoder::new(reader)),
+            Compression::Zstd  => Box::new(ZstdDecoder::with_buffer(reader)
+                .chain_err(|| "unable to initialize zstd decoder")?),
+            Compression::Bzip2 => Box::new(MultiBzDecoder::new(reader)),
+        })
+    }
+}
+
+/// Open a file for reading, decompressing it with the given codec. If no codec is given, it is
+/// detected from the file extension, falling back to the file's magic bytes.
+pub fn open(path: &Path, compression: Option<Compression>) -> Result<Box<dyn Read>> {
+    let file = File::open(path).chain_err(|| format!("unable to open file {}",
+        path.display()))?;
+    decompress(file, path, compression)
+}
+
+/// Decompress data from a reader with the given codec. If no codec is given, it is detected from
+/// the extension of the provided path, falling back to the magic bytes at the start of the data.
+pub fn decompress<'a, R: Read + 'a>(reader: R, path: &Path, compression: Option<Compression>)
+        -> Result<Box<dyn Read + 'a>> {
+    let mut reader = BufReader::new(reader);
+    let compression = match compression.or_else(|| Compression::from_extension(path)) {
+        Some(compression) => compression,
+        None => Compression::from_magic(reader.fill_buf()
+            .chain_err(|| format!("error reading from {}", path.display()))?),
+    };
+    compression.decoder(reader)
+}
@@ -1,15 +1,23 @@
 //! Dataframe configuration structs and methods
 
 use std::collections::HashMap;
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::Read;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
+use glob;
+use regex::Regex;
 use serde_json;
 use toml;
 
 use dataframe::DataStore;
-use dataframe::TransformFields;
+use dataframe::{FitTransform, FittedParams, TransformFields};
+use dataframe::compression::{self, Compression};
+use dataframe::expression::Expression;
+use dataframe::infer;
+use dataframe::output::CsvFormat;
+use dataframe::temporal;
 
 use errors::*;
 
@@ -20,6 +28,10 @@ pub struct DataConfig {
     pub source_files: Vec<SourceFile>,
     /// (Optional) list of transforms on fields in the source files
     pub transforms: Option<Vec<Transform>>,
+    /// (Optional) destination to which the resulting dataframe is written
+    pub output: Option<Output>,
+    /// (Optional) label (target) fields, kept separate from the feature fields of the dataframe
+    pub labels: Option<Labels>,
 }
 
 impl DataConfig {
@@ -63,10 +75,25 @@ impl DataConfig {
                 ErrorKind::DataConfigError("error parsing file as JSON".to_string())))?
         };
         config.fix_paths(&config_file_path)?;
+        config.validate_paths()?;
         config.validate()?;
         Ok(config)
     }
 
+    /// Generate a DataConfig for a delimited source file (with a header row) by inferring the
+    /// type of each field, and whether it has missing values, from a sample of its rows. The
+    /// resulting configuration can be serialized to TOML or JSON for further editing. The source
+    /// file is referred to by its file name, so the configuration should be saved in the same
+    /// directory as the source file
+    pub fn infer(path: &Path, delimiter: u8) -> Result<DataConfig> {
+        Ok(DataConfig {
+            source_files: vec![infer::infer_source_file(path, delimiter)?],
+            transforms: None,
+            output: None,
+            labels: None,
+        })
+    }
+
     fn fix_paths(&mut self, config_file_path: &Path) -> Result<()> {
         let config_file_dir = config_file_path.parent().ok_or(Error::from_kind(
             ErrorKind::DataConfigError(
@@ -77,14 +104,54 @@ impl DataConfig {
                 Error::from_kind(ErrorKind::DataConfigError(
                     "unable to convert pathname to str".to_string())))?.to_string();
         }
+        if let Some(ref mut output) = self.output {
+            let curr_name = output.name.clone();
+            output.name = config_file_dir.join(curr_name).to_str().ok_or(
+                Error::from_kind(ErrorKind::DataConfigError(
+                    "unable to convert pathname to str".to_string())))?.to_string();
+        }
         Ok(())
     }
-    fn validate(&self) -> Result<()> {
+    fn validate_paths(&self) -> Result<()> {
         for source_file in &self.source_files {
-            // check if source_file exists
-            if !source_file.path().exists() {
+            // check if source_file exists (or matches at least one file)
+            let paths = source_file.paths()?;
+            if paths.is_empty() {
                 return Err(Error::from_kind(ErrorKind::DataConfigError(
-                    format!("source file does not exist: {}", source_file.name))))
+                    format!("no files match source file: {}", source_file.name))))
+            }
+            for path in paths {
+                if !path.exists() {
+                    return Err(Error::from_kind(ErrorKind::DataConfigError(
+                        format!("source file does not exist: {}", path.display()))))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Validate the source file and field specifications of this configuration (without checking
+    /// for the existence of source files)
+    pub fn validate(&self) -> Result<()> {
+        for (i, source_file) in self.source_files.iter().enumerate() {
+            // verify join specification
+            if let Some(ref join) = source_file.join {
+                if i == 0 {
+                    return Err(Error::from_kind(ErrorKind::DataConfigError(
+                        format!("first source file {} cannot be joined", source_file.name))))
+                }
+                if join.keys.is_empty() {
+                    return Err(Error::from_kind(ErrorKind::DataConfigError(
+                        format!("join for source file {} requires at least one key",
+                            source_file.name))))
+                }
+                for key in &join.keys {
+                    if !source_file.fields.iter().any(|field| field.target_name() == key) {
+                        return Err(Error::from_kind(ErrorKind::DataConfigError(
+                            format!("join key {} is not a field of source file {}", key,
+                                source_file.name))))
+                    }
+                }
             }
 
             // verify delimiter
@@ -94,22 +161,212 @@ impl DataConfig {
                         format!("invalid delimiter specification: {}", delim))))
                 }
             }
+
+            // verify compression codec
+            source_file.compression()?;
+
+            // verify filters
+            for filter in source_file.filters.iter().flatten() {
+                let field_type = source_file.get_source_field(&filter.source_field)
+                    .map(|field| field.field_type);
+                filter.filter.validate(field_type).chain_err(|| format!(
+                    "invalid filter on field {}", filter.source_field))?;
+            }
+
+            // verify that all fields have column locations for fixed-width source files
+            if source_file.format() == SourceFormat::FixedWidth {
+                for field in &source_file.fields {
+                    match (field.start, field.width) {
+                        (Some(_), Some(width)) if width > 0 => {}
+                        _ => {
+                            return Err(Error::from_kind(ErrorKind::DataConfigError(
+                                format!("field {} requires a start and non-zero width: source \
+                                    file {} is fixed-width", field.source_name, source_file.name))))
+                        }
+                    }
+                }
+            }
+
+            // verify that SQLite source files have a query
+            if source_file.format() == SourceFormat::Sqlite && source_file.query.is_none() {
+                return Err(Error::from_kind(ErrorKind::DataConfigError(
+                    format!("SQLite source file {} requires a query", source_file.name))))
+            }
+
+            // verify that all fields have column indices if source file has no header row
+            let has_columns = match source_file.format() {
+                SourceFormat::Delimited | SourceFormat::Spreadsheet => true,
+                SourceFormat::FixedWidth | SourceFormat::JsonLines | SourceFormat::Parquet
+                    | SourceFormat::ArrowIpc | SourceFormat::Sqlite => false,
+            };
+            if has_columns && !source_file.has_headers() {
+                for field in &source_file.fields {
+                    if field.source_index.is_none() {
+                        return Err(Error::from_kind(ErrorKind::DataConfigError(
+                            format!("field {} requires a source_index: source file {} has no \
+                                headers", field.source_name, source_file.name))))
+                    }
+                }
+            }
+        }
+
+        // verify output delimiter, table and label
+        if let Some(ref output) = self.output {
+            output.csv_format()?;
+            if output.format() == OutputFormat::Sqlite && output.table.is_none() {
+                return Err(Error::from_kind(ErrorKind::DataConfigError(
+                    format!("SQLite output {} requires a table", output.name))))
+            }
+            if output.format() == OutputFormat::LibSvm && output.label.is_none() {
+                return Err(Error::from_kind(ErrorKind::DataConfigError(
+                    format!("LibSVM output {} requires a label field", output.name))))
+            }
+        }
+
+        // verify that expressions parse and only refer to their source fields
+        for transform in self.transforms.iter().flatten() {
+            if let TransformMethod::Expression(ref config) = transform.method {
+                config.parse(&transform.source_fields).chain_err(|| format!(
+                    "invalid expression for transform {}", transform.target_name))?;
+            }
+        }
+
+        // verify label fields
+        if let Some(ref labels) = self.labels {
+            if labels.fields.is_empty() {
+                return Err(Error::from_kind(ErrorKind::DataConfigError(
+                    "labels require at least one field".to_string())))
+            }
+            for (i, field) in labels.fields.iter().enumerate() {
+                if labels.fields[..i].contains(field) {
+                    return Err(Error::from_kind(ErrorKind::DataConfigError(
+                        format!("label field {} specified more than once", field))))
+                }
+            }
         }
         Ok(())
     }
+
+    /// Names of the label fields of this configuration (empty if there is no labels section)
+    pub fn label_fields(&self) -> Vec<&str> {
+        self.labels.as_ref().map_or(vec![], |labels| {
+            labels.fields.iter().map(|field| &field[..]).collect()
+        })
+    }
+}
+
+/// Label (target) fields of a dataframe
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Labels {
+    /// Names of the label fields, in the order of the columns of the label matrix. Label fields
+    /// must be non-text fields of the dataframe
+    pub fields: Vec<String>,
+}
+
+/// Destination and format of the file to which a dataframe is written
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+    /// Output file name
+    pub name: String,
+    /// Format of output file. Defaults to delimited text
+    pub format: Option<OutputFormat>,
+    /// Delimiter used in delimited text output file. Defaults to a comma
+    pub delimiter: Option<String>,
+    /// Whether or not to write a header row of field names to a delimited text output file.
+    /// Defaults to true
+    pub has_headers: Option<bool>,
+    /// (Optional) number of decimal places written for floating-point values in a delimited text
+    /// output file
+    pub float_precision: Option<usize>,
+    /// Value written for missing values in a delimited text output file. Defaults to the empty
+    /// string
+    pub null_value: Option<String>,
+    /// Name of the table created in a SQLite output file
+    pub table: Option<String>,
+    /// Name of the label field of a LibSVM output file
+    pub label: Option<String>,
+}
+
+impl Output {
+    /// Returns the path for this output file
+    pub fn path(&self) -> &Path {
+        Path::new(&self.name[..])
+    }
+
+    /// Returns the format of this output file
+    pub fn format(&self) -> OutputFormat {
+        self.format.unwrap_or(OutputFormat::Delimited)
+    }
+
+    /// Returns the format of delimited text written to this output file
+    pub fn csv_format(&self) -> Result<CsvFormat> {
+        let delimiter = match self.delimiter {
+            Some(ref delim) if delim.len() == 1 => delim.as_bytes()[0],
+            Some(ref delim) => {
+                return Err(Error::from_kind(ErrorKind::DataConfigError(
+                    format!("invalid output delimiter specification: {}", delim))))
+            }
+            None => b',',
+        };
+        Ok(CsvFormat {
+            delimiter,
+            has_headers: self.has_headers.unwrap_or(true),
+            float_precision: self.float_precision,
+            null_value: self.null_value.clone().unwrap_or_default(),
+        })
+    }
+}
+
+/// Format of an output file
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum OutputFormat {
+    /// Delimited text (comma-separated, tab-separated, etc.)
+    Delimited,
+    /// Apache Parquet file
+    Parquet,
+    /// Apache Arrow IPC file
+    ArrowIpc,
+    /// New table of a SQLite database file
+    Sqlite,
+    /// LibSVM-format sparse text, with a label field
+    LibSvm,
 }
 
 /// Source file details
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SourceFile {
-    /// Source file name
+    /// Source file name. Can also be a glob pattern (e.g. "sales_*.csv") or a directory, in which
+    /// case the rows of all matching files are appended together
     pub name: String,
+    /// (Optional) name of a text field to add containing the name of the file each row originated
+    /// from
+    pub filename_field: Option<String>,
+    /// (Optional) key-based join of this source file onto the data of the preceding source files.
+    /// If not specified, fields are combined positionally (row-by-row)
+    pub join: Option<Join>,
+    /// Format of source file. Defaults to delimited text
+    pub format: Option<SourceFormat>,
     /// Delimiter used in source file
     pub delimiter: Option<String>,
+    /// Whether or not the source file has a header row. Defaults to true for delimited and
+    /// spreadsheet source files
+    pub has_headers: Option<bool>,
+    /// (Optional) name of worksheet to load from a spreadsheet source file. Defaults to the first
+    /// worksheet
+    pub sheet: Option<String>,
+    /// (Optional) zero-based index of the spreadsheet row containing the headers (or the first row
+    /// of data, if the spreadsheet has no header row). Rows above are ignored. Defaults to the
+    /// first non-empty row
+    pub header_row: Option<usize>,
     /// List of fields in source file
     pub fields: Vec<Field>,
     /// (Optional) filters used when importing this source file
     pub filters: Option<Vec<Filter>>,
+    /// (Optional) compression codec of source file ("gzip", "zstd", "bzip2", or "none"). Detected
+    /// from the file extension or contents if not specified
+    pub compression: Option<String>,
+    /// (Optional) SQL query run against a SQLite source file to produce its rows
+    pub query: Option<String>,
 }
 
 impl SourceFile {
@@ -118,6 +375,40 @@ impl SourceFile {
         Path::new(&self.name[..])
     }
 
+    /// Returns the paths of all files matched by this source file: the files within the
+    /// directory (if the source file name is a directory), the files matching the glob pattern
+    /// (if the source file name contains glob metacharacters), or the source file path itself
+    pub fn paths(&self) -> Result<Vec<PathBuf>> {
+        let path = self.path();
+        let mut paths = if path.is_dir() {
+            let mut paths = vec![];
+            for entry in fs::read_dir(path).chain_err(|| format!("unable to read directory {}",
+                    self.name))? {
+                let entry_path = entry.chain_err(|| format!("unable to read directory {}",
+                    self.name))?.path();
+                if entry_path.is_file() {
+                    paths.push(entry_path);
+                }
+            }
+            paths
+        } else if self.name.contains(['*', '?', '[']) {
+            let mut paths = vec![];
+            for entry in glob::glob(&self.name).chain_err(|| Error::from_kind(
+                    ErrorKind::DataConfigError(format!("invalid glob pattern: {}", self.name))))? {
+                let entry_path = entry.chain_err(|| format!("unable to read files matching {}",
+                    self.name))?;
+                if entry_path.is_file() {
+                    paths.push(entry_path);
+                }
+            }
+            paths
+        } else {
+            vec![path.to_path_buf()]
+        };
+        paths.sort();
+        Ok(paths)
+    }
+
     /// Returns the delimiter used in this source file
     pub fn delimiter(&self) -> Result<u8> {
         Ok(match self.delimiter {
@@ -129,6 +420,40 @@ impl SourceFile {
         })
     }
 
+    /// Returns the format of this source file
+    pub fn format(&self) -> SourceFormat {
+        self.format.unwrap_or(SourceFormat::Delimited)
+    }
+
+    /// Whether or not this source file has a header row. Defaults to true for delimited and
+    /// spreadsheet source files, false otherwise
+    pub fn has_headers(&self) -> bool {
+        self.has_headers.unwrap_or(match self.format() {
+            SourceFormat::Delimited | SourceFormat::Spreadsheet => true,
+            SourceFormat::FixedWidth | SourceFormat::JsonLines | SourceFormat::Parquet
+                | SourceFormat::ArrowIpc | SourceFormat::Sqlite => false,
+        })
+    }
+
+    /// Returns the compression codec specified for this source file (if any)
+    pub fn compression(&self) -> Result<Option<Compression>> {
+        match self.compression {
+            Some(ref codec) => Compression::from_name(codec).map(Some),
+            None            => Ok(None)
+        }
+    }
+
+    /// Opens one of the files matched by this source file for reading, decompressing it if
+    /// necessary
+    pub fn open(&self, path: &Path) -> Result<Box<dyn Read>> {
+        compression::open(path, self.compression()?)
+    }
+
+    /// Wraps a reader providing this source file's data, decompressing it if necessary
+    pub fn decompress<'a, R: Read + 'a>(&self, reader: R) -> Result<Box<dyn Read + 'a>> {
+        compression::decompress(reader, self.path(), self.compression()?)
+    }
+
     /// Gets field details, given a specific field name
     pub fn get_source_field(&self, s: &String) -> Option<&Field> {
         self.fields.iter().find(|&&ref field| field.source_name == *s)
@@ -140,12 +465,27 @@ impl SourceFile {
 pub struct Field {
     /// Name of field
     pub source_name: String,
+    /// (Optional) zero-based column index of field, used for source files without a header row
+    pub source_index: Option<usize>,
+    /// (Optional) byte offset of the start of field, used for fixed-width source files
+    pub start: Option<usize>,
+    /// (Optional) width of field in bytes, used for fixed-width source files
+    pub width: Option<usize>,
+    /// (Optional) whether or not to trim surrounding whitespace from field values in fixed-width
+    /// source files. Defaults to true
+    pub trim: Option<bool>,
     /// (Optional) transformed name of field
     pub target_name: Option<String>,
     /// Field type
     pub field_type: FieldType,
     /// Whether or not to add this field to the dataframe. Defaults to true
     pub add_to_frame: Option<bool>,
+    /// (Optional) format of date and date-time field values, in strftime syntax (e.g.
+    /// "%d/%m/%Y"). Defaults to ISO 8601
+    pub format: Option<String>,
+    /// (Optional) list of source values (e.g. "", "NA", "NULL") which denote a missing value.
+    /// Defaults to the empty string for non-text fields, and no values for text fields
+    pub null_values: Option<Vec<String>>,
 }
 
 impl Field {
@@ -159,6 +499,66 @@ impl Field {
     pub fn add_to_frame(&self) -> bool {
         self.add_to_frame.unwrap_or(true)
     }
+
+    /// Whether or not to trim whitespace from values of this field in fixed-width source files
+    pub fn trim(&self) -> bool {
+        self.trim.unwrap_or(true)
+    }
+
+    /// Format of date and date-time field values, if not ISO 8601
+    pub fn format(&self) -> Option<&str> {
+        self.format.as_ref().map(|format| &format[..])
+    }
+
+    /// Whether or not a source value of this field denotes a missing value
+    pub fn is_null_value(&self, value_str: &str) -> bool {
+        match self.null_values {
+            Some(ref null_values) => null_values.iter().any(|null_value| null_value == value_str),
+            None => self.field_type != FieldType::Text && value_str.is_empty(),
+        }
+    }
+}
+
+/// Format of a source file
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SourceFormat {
+    /// Delimited text (comma-separated, tab-separated, etc.)
+    Delimited,
+    /// Fixed-width text columns
+    FixedWidth,
+    /// Newline-delimited JSON objects, with field source names specifying JSON pointers (e.g.
+    /// "/user/name") or dotted paths (e.g. "user.name") into each object
+    JsonLines,
+    /// Spreadsheet worksheet (OpenDocument .ods or Excel .xlsx, .xls, .xlsb)
+    Spreadsheet,
+    /// Apache Parquet file, with field source names specifying column names
+    Parquet,
+    /// Apache Arrow IPC file, with field source names specifying column names
+    ArrowIpc,
+    /// SQLite database file, queried with the source file's SQL query, with field source names
+    /// specifying result column names
+    Sqlite,
+}
+
+/// Key-based join of a source file onto the data of the preceding source files
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Join {
+    /// Method of join
+    pub method: JoinMethod,
+    /// List of one or more key fields (target field names) to match rows on. Key fields must exist
+    /// in both this source file and the preceding source files
+    pub keys: Vec<String>,
+}
+
+/// Type of join
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum JoinMethod {
+    /// Only rows with keys existing on both sides of the join
+    Inner,
+    /// All rows from the left side of the join, with matching rows from the right side
+    Left,
+    /// All rows from both sides of the join
+    Outer,
 }
 
 /// Specification of the type of field
@@ -173,7 +573,13 @@ pub enum FieldType {
     /// Boolean (yes/no) field
     Boolean,
     /// Floating-point field
-    Float
+    Float,
+    /// Calendar date field
+    Date,
+    /// Date and time field (without time zone; values with a UTC offset are converted to UTC)
+    DateTime,
+    /// Duration (elapsed time) field, with values of the form "HH:MM:SS" or a number of seconds
+    Duration,
 }
 
 /// Source file filter
@@ -190,6 +596,15 @@ impl Filter {
     pub fn apply(&self, value_str: &String) -> Result<bool> {
         self.filter.apply(value_str)
     }
+    /// Apply this filter to a value of a field with the specified date or date-time format
+    pub fn apply_formatted(&self, value_str: &String, format: Option<&str>) -> Result<bool> {
+        self.filter.apply_formatted(value_str, format)
+    }
+    /// Apply this filter to a missing value, returning whether or not to include the value in the
+    /// resulting data frame
+    pub fn apply_null(&self) -> bool {
+        self.filter.apply_null()
+    }
 }
 
 /// Filter method
@@ -202,18 +617,299 @@ pub enum FilterMethod {
     MatchNot(MatchConfig),
     /// Filter based on an inequality comparison (less than, greater than)
     Inequality(InequalityConfig),
+    /// Filter keeping only missing values
+    IsNull,
+    /// Filter keeping only non-missing values
+    NotNull,
+    /// Filter keeping text values which match a regular expression
+    Regex(RegexConfig),
+    /// Filter keeping text values which start with a particular string
+    StartsWith(TextFilterConfig),
+    /// Filter keeping text values which end with a particular string
+    EndsWith(TextFilterConfig),
+    /// Filter keeping text values which contain a particular string
+    Contains(TextFilterConfig),
+    /// Filter keeping values which match any of a set of values
+    In(InConfig),
+    /// Filter keeping values within a range (inclusive of both ends)
+    Between(BetweenConfig),
+    /// Filter keeping values which pass all of the nested filter methods
+    And(FilterListConfig),
+    /// Filter keeping values which pass any of the nested filter methods
+    Or(FilterListConfig),
+    /// Filter keeping values which don't pass the nested filter method
+    Not(NotFilterConfig),
 }
 impl FilterMethod {
     /// Apply the filter method to the value
     pub fn apply(&self, value_str: &String) -> Result<bool> {
+        self.apply_formatted(value_str, None)
+    }
+    /// Apply the filter method to a value of a field with the specified date or date-time format
+    pub fn apply_formatted(&self, value_str: &String, format: Option<&str>) -> Result<bool> {
         match *self {
             FilterMethod::Match(ref config) => { config.does_match(value_str) }
             FilterMethod::MatchNot(ref config) => { config.does_match(value_str).map(|b| !b) }
-            FilterMethod::Inequality(ref config) => { config.does_satisfy(value_str) }
+            FilterMethod::Inequality(ref config) => {
+                config.does_satisfy_formatted(value_str, format)
+            }
+            FilterMethod::IsNull => { Ok(false) }
+            FilterMethod::NotNull => { Ok(true) }
+            FilterMethod::Regex(ref config) => { Ok(config.regex()?.is_match(value_str)) }
+            FilterMethod::StartsWith(ref config) => { Ok(value_str.starts_with(&config.text[..])) }
+            FilterMethod::EndsWith(ref config) => { Ok(value_str.ends_with(&config.text[..])) }
+            FilterMethod::Contains(ref config) => { Ok(value_str.contains(&config.text[..])) }
+            FilterMethod::In(ref config) => { Ok(config.contains(value_str)) }
+            FilterMethod::Between(ref config) => {
+                config.does_contain_formatted(value_str, format)
+            }
+            FilterMethod::And(ref config) => {
+                for filter in &config.filters {
+                    if !filter.apply_formatted(value_str, format)? {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
+            FilterMethod::Or(ref config) => {
+                for filter in &config.filters {
+                    if filter.apply_formatted(value_str, format)? {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            }
+            FilterMethod::Not(ref config) => {
+                config.filter.apply_formatted(value_str, format).map(|b| !b)
+            }
+        }
+    }
+    /// Apply the filter method to a missing value. Missing values only pass the `IsNull` filter
+    /// method (or combinations of filter methods which pass it, e.g. `Not` of any other method).
+    pub fn apply_null(&self) -> bool {
+        match *self {
+            FilterMethod::IsNull => true,
+            FilterMethod::And(ref config) => config.filters.iter().all(|f| f.apply_null()),
+            FilterMethod::Or(ref config) => config.filters.iter().any(|f| f.apply_null()),
+            FilterMethod::Not(ref config) => !config.filter.apply_null(),
+            _                    => false,
+        }
+    }
+    /// Validate this filter method (and any nested filter methods) for a field of the specified
+    /// type (if known), compiling any regular expressions
+    pub fn validate(&self, field_type: Option<FieldType>) -> Result<()> {
+        match *self {
+            FilterMethod::Regex(ref config) => config.regex().map(|_| ()),
+            FilterMethod::In(ref config) => config.validate(field_type),
+            FilterMethod::Between(ref config) => config.validate(),
+            FilterMethod::And(ref config) | FilterMethod::Or(ref config) => {
+                if config.filters.is_empty() {
+                    return Err(Error::from_kind(ErrorKind::DataConfigError(
+                        "And and Or filters require at least one nested filter".to_string())));
+                }
+                for filter in &config.filters {
+                    filter.validate(field_type)?;
+                }
+                Ok(())
+            }
+            FilterMethod::Not(ref config) => config.filter.validate(field_type),
+            _ => Ok(()),
         }
     }
 }
 
+/// Configuration details for regular expression filters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegexConfig {
+    /// Regular expression, which can match anywhere in a value (use `^` and `$` to match whole
+    /// values)
+    pub pattern: String,
+    /// Compiled regular expression (compiled when validating the configuration)
+    #[serde(skip)]
+    compiled: OnceLock<Regex>,
+}
+impl RegexConfig {
+    /// Create a regular expression filter configuration for a pattern
+    pub fn new<T: Into<String>>(pattern: T) -> RegexConfig {
+        RegexConfig { pattern: pattern.into(), compiled: OnceLock::new() }
+    }
+    /// Compiled regular expression, compiled the first time it is requested
+    pub fn regex(&self) -> Result<&Regex> {
+        if let Some(regex) = self.compiled.get() {
+            return Ok(regex);
+        }
+        let regex = Regex::new(&self.pattern).map_err(|e| Error::from_kind(
+            ErrorKind::DataConfigError(format!("invalid regular expression {}: {}", self.pattern,
+            e))))?;
+        Ok(self.compiled.get_or_init(|| regex))
+    }
+}
+impl PartialEq for RegexConfig {
+    fn eq(&self, other: &RegexConfig) -> bool {
+        self.pattern == other.pattern
+    }
+}
+
+/// Configuration details for prefix, suffix and substring filters
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextFilterConfig {
+    /// String to search for
+    pub text: String,
+}
+
+/// Value of a set-membership filter
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FilterValue {
+    /// Boolean value
+    Boolean(bool),
+    /// Integer value
+    Integer(i64),
+    /// Floating-point value
+    Float(f64),
+    /// Text value
+    Text(String),
+}
+impl FilterValue {
+    /// Checks whether a value is equal to this filter value. Values which can't be parsed as the
+    /// type of this filter value don't match.
+    pub fn does_match(&self, value_str: &str) -> bool {
+        match *self {
+            FilterValue::Boolean(b) => value_str.parse::<bool>() == Ok(b),
+            FilterValue::Integer(i) => match value_str.parse::<i64>() {
+                Ok(value) => value == i,
+                // integers can also match values of floating-point fields
+                Err(_)    => value_str.parse::<f64>() == Ok(i as f64),
+            },
+            FilterValue::Float(f) => value_str.parse::<f64>() == Ok(f),
+            FilterValue::Text(ref s) => s == value_str,
+        }
+    }
+    /// Whether or not this filter value can be compared with the values of a field of the
+    /// specified type
+    pub fn is_compatible(&self, field_type: FieldType) -> bool {
+        match *self {
+            FilterValue::Boolean(_) => field_type == FieldType::Boolean,
+            FilterValue::Integer(_) => matches!(field_type,
+                FieldType::Unsigned | FieldType::Signed | FieldType::Float),
+            FilterValue::Float(_) => field_type == FieldType::Float,
+            // text values match the unparsed values of text and temporal fields
+            FilterValue::Text(_) => matches!(field_type,
+                FieldType::Text | FieldType::Date | FieldType::DateTime | FieldType::Duration),
+        }
+    }
+}
+
+/// Configuration details for set-membership filters
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InConfig {
+    /// Values to match (text, integer, floating-point or boolean values)
+    pub values: Vec<FilterValue>,
+}
+impl InConfig {
+    /// Checks whether a value matches any of the values of this filter
+    pub fn contains(&self, value_str: &str) -> bool {
+        self.values.iter().any(|value| value.does_match(value_str))
+    }
+    /// Check that this filter has values, which are compatible with the type of the field (if
+    /// known)
+    fn validate(&self, field_type: Option<FieldType>) -> Result<()> {
+        if self.values.is_empty() {
+            return Err(Error::from_kind(ErrorKind::DataConfigError(
+                "In filters require at least one value".to_string())));
+        }
+        if let Some(field_type) = field_type {
+            if let Some(value) = self.values.iter().find(|value| !value.is_compatible(field_type)) {
+                return Err(Error::from_kind(ErrorKind::DataConfigError(format!(
+                    "In filter value {:?} cannot match values of a {:?} field", value,
+                    field_type))));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Configuration details for range-based filters. Each range is a `[min, max]` pair, and values
+/// equal to either end are within the range.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BetweenConfig {
+    /// Signed integer range
+    signed: Option<(i64, i64)>,
+    /// Unsigned integer range
+    unsigned: Option<(u64, u64)>,
+    /// Floating point range
+    float: Option<(f64, f64)>,
+    /// Date range (ISO 8601, e.g. "2017-06-30")
+    date: Option<(String, String)>,
+    /// Date-time range (ISO 8601, e.g. "2017-06-30T14:05:00")
+    datetime: Option<(String, String)>,
+    /// Duration range (e.g. "01:30:00")
+    duration: Option<(String, String)>,
+}
+impl BetweenConfig {
+    /// Checks to see if a value is within the range
+    pub fn does_contain(&self, value_str: &str) -> Result<bool> {
+        self.does_contain_formatted(value_str, None)
+    }
+    /// Checks to see if a value (of a field with the specified date or date-time format) is within
+    /// the range
+    pub fn does_contain_formatted(&self, value_str: &str, format: Option<&str>)
+            -> Result<bool> {
+        fn within<T: PartialOrd>(value: T, min: T, max: T) -> bool {
+            min <= value && value <= max
+        }
+        Ok(if let Some((min, max)) = self.signed {
+            within(value_str.parse::<i64>().chain_err(|| "signed integer parse error")?, min, max)
+        } else if let Some((min, max)) = self.unsigned {
+            within(value_str.parse::<u64>().chain_err(|| "unsigned integer parse error")?, min,
+                max)
+        } else if let Some((min, max)) = self.float {
+            within(value_str.parse::<f64>().chain_err(|| "float parse error")?, min, max)
+        } else if let Some((ref min, ref max)) = self.date {
+            within(temporal::parse_date(value_str, format)?, temporal::parse_date(min, None)?,
+                temporal::parse_date(max, None)?)
+        } else if let Some((ref min, ref max)) = self.datetime {
+            within(temporal::parse_datetime(value_str, format)?,
+                temporal::parse_datetime(min, None)?, temporal::parse_datetime(max, None)?)
+        } else if let Some((ref min, ref max)) = self.duration {
+            within(temporal::parse_duration(value_str)?, temporal::parse_duration(min)?,
+                temporal::parse_duration(max)?)
+        } else {
+            return Err(ErrorKind::DataConfigError("missing between range".to_string()).into());
+        })
+    }
+    /// Check that this filter has a range, with valid temporal bounds
+    fn validate(&self) -> Result<()> {
+        if let Some((ref min, ref max)) = self.date {
+            temporal::parse_date(min, None)?;
+            temporal::parse_date(max, None)?;
+        } else if let Some((ref min, ref max)) = self.datetime {
+            temporal::parse_datetime(min, None)?;
+            temporal::parse_datetime(max, None)?;
+        } else if let Some((ref min, ref max)) = self.duration {
+            temporal::parse_duration(min)?;
+            temporal::parse_duration(max)?;
+        } else if self.signed.is_none() && self.unsigned.is_none() && self.float.is_none() {
+            return Err(ErrorKind::DataConfigError("missing between range".to_string()).into());
+        }
+        Ok(())
+    }
+}
+
+/// Configuration details for filters combining a list of nested filter methods
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FilterListConfig {
+    /// Nested filter methods
+    pub filters: Vec<FilterMethod>,
+}
+
+/// Configuration details for filters negating a nested filter method
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotFilterConfig {
+    /// Nested filter method
+    pub filter: Box<FilterMethod>,
+}
+
 /// Configuration details for matching-based filters
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MatchConfig {
@@ -259,11 +955,22 @@ pub struct InequalityConfig {
     unsigned: Option<u64>,
     /// Floating point inequality target
     float: Option<f64>,
-
+    /// Date inequality target (ISO 8601, e.g. "2017-06-30")
+    date: Option<String>,
+    /// Date-time inequality target (ISO 8601, e.g. "2017-06-30T14:05:00")
+    datetime: Option<String>,
+    /// Duration inequality target (e.g. "01:30:00")
+    duration: Option<String>,
 }
 impl InequalityConfig {
     /// Checks to see if value satisfies the inequality
     pub fn does_satisfy(&self, value_str: &String) -> Result<bool> {
+        self.does_satisfy_formatted(value_str, None)
+    }
+    /// Checks to see if value (of a field with the specified date or date-time format) satisfies
+    /// the inequality
+    pub fn does_satisfy_formatted(&self, value_str: &String, format: Option<&str>)
+            -> Result<bool> {
         Ok(if let Some(i) = self.signed {
             self.inequality.does_satisfy(
                 value_str.parse::<i64>().chain_err(|| "signed integer parse error")?, i)
@@ -273,6 +980,15 @@ impl InequalityConfig {
         } else if let Some(f) = self.float {
             self.inequality.does_satisfy(
                 value_str.parse::<f64>().chain_err(|| "float parse error")?, f)
+        } else if let Some(ref d) = self.date {
+            self.inequality.does_satisfy(temporal::parse_date(value_str, format)?,
+                temporal::parse_date(d, None)?)
+        } else if let Some(ref dt) = self.datetime {
+            self.inequality.does_satisfy(temporal::parse_datetime(value_str, format)?,
+                temporal::parse_datetime(dt, None)?)
+        } else if let Some(ref d) = self.duration {
+            self.inequality.does_satisfy(temporal::parse_duration(value_str)?,
+                temporal::parse_duration(d)?)
         } else {
             return Err(ErrorKind::DataConfigError("missing inequality value".to_string()).into());
         })
@@ -321,8 +1037,8 @@ impl Transform {
     pub fn add_to_frame(&self) -> bool {
         self.add_to_frame.unwrap_or(true)
     }
-    /// Target type for the transformed field
-    pub fn target_type(&self) -> FieldType {
+    /// Target type for the transformed field, or `None` if the type depends on the source fields
+    pub fn target_type(&self) -> Option<FieldType> {
         self.method.target_type()
     }
     /// Check whether or not the source exists is the specified data store for this transform
@@ -337,6 +1053,38 @@ impl Transform {
         check_transform_source(&self.source_fields, original)?;
         self.method.transform(original, &self.source_fields, &self.target_name)
     }
+    /// Whether or not this transform learns parameters from the data it transforms
+    pub fn is_stateful(&self) -> bool {
+        self.method.fit_transform().is_some()
+    }
+    /// Perform the transform using the specified data store. Stateful transforms use the provided
+    /// fitted parameters, or are fitted to the data store if no parameters are provided. Returns
+    /// the transformed data and the fitted parameters used (`None` for stateless transforms).
+    pub fn transform_fitted(&self, original: &DataStore, params: Option<&FittedParams>)
+            -> Result<(DataStore, Option<FittedParams>)> {
+        check_transform_source(&self.source_fields, original)?;
+        let fit_transform = match self.method.fit_transform() {
+            Some(fit_transform) => fit_transform,
+            None => { return Ok((self.transform(original)?, None)); }
+        };
+        let params = match params {
+            Some(params) => params.clone(),
+            None => fit_transform.fit(original, &self.source_fields)?,
+        };
+        let transformed = fit_transform.apply(original, &self.source_fields, &self.target_name,
+            &params)?;
+        Ok((transformed, Some(params)))
+    }
+    /// Map the fields generated by this transform (in the specified data store) back to values of
+    /// its source field, using the fitted parameters of stateful transforms
+    pub fn inverse(&self, transformed: &DataStore, params: Option<&FittedParams>)
+            -> Result<DataStore> {
+        if self.is_stateful() && params.is_none() {
+            return Err(Error::from_kind(ErrorKind::DataConfigError(
+                format!("no fitted parameters for transform {}", self.target_name))));
+        }
+        self.method.inverse(transformed, &self.source_fields, &self.target_name, params)
+    }
 }
 
 fn check_transform_source(source_fields: &Vec<String>, ds: &DataStore) -> Result<()> {
@@ -368,19 +1116,40 @@ pub enum TransformMethod {
     Normalize(NormalizeConfig),
     /// Scaling for a floating-point field
     Scale(ScaleConfig),
+    /// Imputation of missing values
+    Impute(ImputeConfig),
+    /// Extraction of date parts (year, month, hour, etc.) from a date or date-time field
+    DateParts(DatePartsConfig),
+    /// Computation of a field from an expression over other fields
+    Expression(ExpressionConfig),
 }
 
 impl TransformMethod {
-    /// The field type that result from this transformation method
-    pub fn target_type(&self) -> FieldType {
+    /// The field type that result from this transformation method, or `None` if the field type
+    /// depends on the source fields
+    pub fn target_type(&self) -> Option<FieldType> {
+        match *self {
+            TransformMethod::Convert(ref config)    => { Some(config.target_type()) }
+            TransformMethod::Map(_)                 => { Some(FieldType::Text) }
+            TransformMethod::Concatenate(_)         => { Some(FieldType::Text) }
+            TransformMethod::VectorizeOneHot(_)     => { Some(FieldType::Float) }
+            TransformMethod::VectorizeHash(_)       => { Some(FieldType::Float) }
+            TransformMethod::Normalize(_)           => { Some(FieldType::Float) }
+            TransformMethod::Scale(_)               => { Some(FieldType::Float) }
+            TransformMethod::Impute(_)              => { None }
+            TransformMethod::DateParts(_)           => { None }
+            TransformMethod::Expression(_)          => { None }
+        }
+    }
+    /// The fit / apply implementation of this transformation method, or `None` if this method
+    /// does not learn any parameters from the data
+    pub fn fit_transform(&self) -> Option<&dyn FitTransform> {
         match *self {
-            TransformMethod::Convert(ref config)    => { config.target_type() }
-            TransformMethod::Map(_)                 => { FieldType::Text }
-            TransformMethod::Concatenate(_)         => { FieldType::Text }
-            TransformMethod::VectorizeOneHot(_)     => { FieldType::Float }
-            TransformMethod::VectorizeHash(_)       => { FieldType::Float }
-            TransformMethod::Normalize(_)           => { FieldType::Float }
-            TransformMethod::Scale(_)               => { FieldType::Float }
+            TransformMethod::VectorizeOneHot(ref config) => { Some(config) }
+            TransformMethod::Normalize(ref config)       => { Some(config) }
+            TransformMethod::Scale(ref config)           => { Some(config) }
+            TransformMethod::Impute(ref config)          => { Some(config) }
+            _                                            => { None }
         }
     }
     /// Use this method to transform a data store's one or more source fields into a field with the
@@ -409,6 +1178,52 @@ impl TransformMethod {
             TransformMethod::Scale(ref config)           => {
                 config.transform_fields(orig_ds, sfs, tn)
             }
+            TransformMethod::Impute(ref config)          => {
+                config.transform_fields(orig_ds, sfs, tn)
+            }
+            TransformMethod::DateParts(ref config)       => {
+                config.transform_fields(orig_ds, sfs, tn)
+            }
+            TransformMethod::Expression(ref config)      => {
+                config.transform_fields(orig_ds, sfs, tn)
+            }
+        }
+    }
+    /// Use this method to map a data store's field(s) generated by a transform with the target
+    /// name back to values of the source field
+    pub fn inverse(&self, tf_ds: &DataStore, sfs: &[String], tn: &str,
+            params: Option<&FittedParams>) -> Result<DataStore> {
+        match *self {
+            TransformMethod::Convert(ref config)         => {
+                config.inverse_fields(tf_ds, sfs, tn, params)
+            }
+            TransformMethod::Map(ref config)             => {
+                config.inverse_fields(tf_ds, sfs, tn, params)
+            }
+            TransformMethod::Concatenate(ref config)     => {
+                config.inverse_fields(tf_ds, sfs, tn, params)
+            }
+            TransformMethod::VectorizeOneHot(ref config) => {
+                config.inverse_fields(tf_ds, sfs, tn, params)
+            }
+            TransformMethod::VectorizeHash(ref config)   => {
+                config.inverse_fields(tf_ds, sfs, tn, params)
+            }
+            TransformMethod::Normalize(ref config)       => {
+                config.inverse_fields(tf_ds, sfs, tn, params)
+            }
+            TransformMethod::Scale(ref config)           => {
+                config.inverse_fields(tf_ds, sfs, tn, params)
+            }
+            TransformMethod::Impute(ref config)          => {
+                config.inverse_fields(tf_ds, sfs, tn, params)
+            }
+            TransformMethod::DateParts(ref config)       => {
+                config.inverse_fields(tf_ds, sfs, tn, params)
+            }
+            TransformMethod::Expression(ref config)      => {
+                config.inverse_fields(tf_ds, sfs, tn, params)
+            }
         }
     }
 }
@@ -417,6 +1232,9 @@ impl TransformMethod {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ConvertConfig {
     target_type: FieldType,
+    /// (Optional) format for parsing dates and date-times from text, or formatting them as text,
+    /// in strftime syntax. Defaults to ISO 8601
+    format: Option<String>,
 }
 
 impl ConvertConfig {
@@ -424,6 +1242,10 @@ impl ConvertConfig {
     pub fn target_type(&self) -> FieldType {
         self.target_type
     }
+    /// Format for converting dates and date-times to or from text, if not ISO 8601
+    pub fn format(&self) -> Option<&str> {
+        self.format.as_ref().map(|format| &format[..])
+    }
 }
 
 /// Configuration of a mapping transformation
@@ -539,3 +1361,174 @@ impl ScaleConfig {
         self.min_value.is_some() || self.max_value.is_some()
     }
 }
+
+/// Strategy for imputing missing values
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ImputeStrategy {
+    /// Replace missing values with a constant value
+    Constant,
+    /// Replace missing values with the mean of the present values (numeric fields only)
+    Mean,
+    /// Replace missing values with the median of the present values (numeric fields only)
+    Median,
+    /// Replace missing values with the most common present value
+    Mode,
+    /// Replace missing values with the previous present value (leading missing values are
+    /// replaced with the first present value)
+    ForwardFill,
+}
+
+/// Configuration for a missing value imputation transformation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImputeConfig {
+    /// Imputation strategy
+    strategy: ImputeStrategy,
+
+    /// Text-based constant value (also used for date, date-time and duration fields, in ISO 8601
+    /// and "HH:MM:SS" formats)
+    text: Option<String>,
+    /// Signed integer constant value
+    signed: Option<i64>,
+    /// Unsigned integer constant value
+    unsigned: Option<u64>,
+    /// Boolean constant value
+    boolean: Option<bool>,
+    /// Floating-point constant value
+    float: Option<f64>,
+
+    /// Whether or not to add a boolean indicator field (named with a "_was_missing" suffix)
+    /// specifying which values were imputed. Defaults to false
+    add_indicator: Option<bool>,
+}
+
+impl ImputeConfig {
+    /// Imputation strategy
+    pub fn strategy(&self) -> ImputeStrategy {
+        self.strategy
+    }
+    /// Constant value (in unparsed string form) used by the constant imputation strategy
+    pub fn constant(&self) -> Option<String> {
+        self.text.clone()
+            .or_else(|| self.signed.map(|i| i.to_string()))
+            .or_else(|| self.unsigned.map(|u| u.to_string()))
+            .or_else(|| self.boolean.map(|b| b.to_string()))
+            .or_else(|| self.float.map(|f| f.to_string()))
+    }
+    /// Whether or not to add a missing value indicator field
+    pub fn add_indicator(&self) -> bool {
+        self.add_indicator.unwrap_or(false)
+    }
+}
+
+/// Part of a date or date-time value extracted by a date-part transformation
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DatePart {
+    /// Year (signed integer)
+    Year,
+    /// Month of the year, 1 to 12 (unsigned integer)
+    Month,
+    /// Day of the month, 1 to 31 (unsigned integer)
+    Day,
+    /// Day of the week, 0 (Monday) to 6 (Sunday) (unsigned integer)
+    DayOfWeek,
+    /// Day of the year, 1 to 366 (unsigned integer)
+    DayOfYear,
+    /// Hour of the day, 0 to 23 (unsigned integer, date-time fields only)
+    Hour,
+    /// Minute of the hour, 0 to 59 (unsigned integer, date-time fields only)
+    Minute,
+    /// Whether or not the day is a Saturday or Sunday (boolean)
+    IsWeekend,
+    /// Sine of the month's angle around the year (float)
+    MonthSin,
+    /// Cosine of the month's angle around the year (float)
+    MonthCos,
+    /// Sine of the day's angle around the week (float)
+    DayOfWeekSin,
+    /// Cosine of the day's angle around the week (float)
+    DayOfWeekCos,
+    /// Sine of the hour's angle around the day (float, date-time fields only)
+    HourSin,
+    /// Cosine of the hour's angle around the day (float, date-time fields only)
+    HourCos,
+}
+
+impl DatePart {
+    /// Suffix appended to the transform's target name to name the field of this date part
+    pub fn suffix(&self) -> &'static str {
+        match *self {
+            DatePart::Year          => "year",
+            DatePart::Month         => "month",
+            DatePart::Day           => "day",
+            DatePart::DayOfWeek     => "day_of_week",
+            DatePart::DayOfYear     => "day_of_year",
+            DatePart::Hour          => "hour",
+            DatePart::Minute        => "minute",
+            DatePart::IsWeekend     => "is_weekend",
+            DatePart::MonthSin      => "month_sin",
+            DatePart::MonthCos      => "month_cos",
+            DatePart::DayOfWeekSin  => "day_of_week_sin",
+            DatePart::DayOfWeekCos  => "day_of_week_cos",
+            DatePart::HourSin       => "hour_sin",
+            DatePart::HourCos       => "hour_cos",
+        }
+    }
+    /// Field type of the field of this date part
+    pub fn field_type(&self) -> FieldType {
+        match *self {
+            DatePart::Year => FieldType::Signed,
+            DatePart::Month | DatePart::Day | DatePart::DayOfWeek | DatePart::DayOfYear
+                | DatePart::Hour | DatePart::Minute => FieldType::Unsigned,
+            DatePart::IsWeekend => FieldType::Boolean,
+            DatePart::MonthSin | DatePart::MonthCos | DatePart::DayOfWeekSin
+                | DatePart::DayOfWeekCos | DatePart::HourSin | DatePart::HourCos => {
+                FieldType::Float
+            }
+        }
+    }
+    /// Whether or not this date part requires a time of day (and so a date-time source field)
+    pub fn requires_time(&self) -> bool {
+        matches!(*self, DatePart::Hour | DatePart::Minute | DatePart::HourSin | DatePart::HourCos)
+    }
+}
+
+/// Configuration for a date-part extraction transformation. Each date part is added as a field
+/// named with the target name followed by the part's suffix (e.g. "purchased_day_of_week")
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DatePartsConfig {
+    /// Date parts to extract (defaults to year, month, day and day of the week)
+    parts: Option<Vec<DatePart>>,
+}
+
+impl DatePartsConfig {
+    /// Date parts extracted by this transformation
+    pub fn parts(&self) -> Vec<DatePart> {
+        self.parts.clone().unwrap_or_else(|| vec![DatePart::Year, DatePart::Month, DatePart::Day,
+            DatePart::DayOfWeek])
+    }
+}
+
+/// Configuration for an expression transformation, which computes a field from arithmetic,
+/// comparison, logical and string operations on other fields (e.g. `log(age + 1)` or
+/// `if(gender_code == "M", 0, 1)`). All fields referred to by the expression must be listed as
+/// source fields.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExpressionConfig {
+    /// Expression to evaluate
+    pub expression: String,
+}
+
+impl ExpressionConfig {
+    /// Parse the expression of this transformation, checking that it only refers to the specified
+    /// source fields
+    pub fn parse(&self, source_fields: &[String]) -> Result<Expression> {
+        let expression = Expression::parse(&self.expression)?;
+        for field in expression.fields() {
+            if !source_fields.iter().any(|source_field| source_field == field) {
+                return Err(Error::from_kind(ErrorKind::DataConfigError(format!(
+                    "expression refers to field {} which is not a source field", field))));
+            }
+        }
+        Ok(expression)
+    }
+}
@@ -1,13 +1,17 @@
 //! Field conversion methods
 
-use std::fmt;
+use std::f64;
+use std::fmt::{self, Display};
 
 use errors::*;
 
 use num::traits::cast::ToPrimitive;
 
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+
 use dataframe::DataStore;
 use dataframe::config::FieldType;
+use dataframe::temporal;
 
 pub enum ConvertType {
     UnsignedToUnsigned,
@@ -39,14 +43,44 @@ pub enum ConvertType {
     FloatToText,
     FloatToBoolean,
     FloatToFloat,
+    FloatToDateTime,
+    FloatToDuration,
+
+    UnsignedToDateTime,
+    UnsignedToDuration,
+    SignedToDateTime,
+    SignedToDuration,
+    TextToDate,
+    TextToDateTime,
+    TextToDuration,
+
+    DateToSigned,
+    DateToText,
+    DateToFloat,
+    DateToDate,
+    DateToDateTime,
+
+    DateTimeToSigned,
+    DateTimeToText,
+    DateTimeToFloat,
+    DateTimeToDate,
+    DateTimeToDateTime,
+
+    DurationToSigned,
+    DurationToText,
+    DurationToFloat,
+    DurationToDuration,
 }
 
+/// Convert a field to a different field type. Date and date-time values are parsed from (or
+/// formatted to) text using the specified format, or ISO 8601 if none. Date and date-time values
+/// convert to and from numbers as seconds since the Unix epoch, and durations as seconds.
 pub fn convert_field(
         source_field: &String, source_type: FieldType,
         target_field: &String, target_type: FieldType,
-        orig_ds: &DataStore) -> Result<DataStore> {
+        format: Option<&str>, orig_ds: &DataStore) -> Result<DataStore> {
     let mut conv_data = DataStore::empty();
-    match gen_convert_type(source_type, target_type) {
+    match gen_convert_type(source_type, target_type)? {
         ConvertType::UnsignedToUnsigned => { conv_data.merge_unsigned(target_field,
             orig_ds.get_unsigned_field(source_field).unwrap().vec_convert())?; }
         ConvertType::UnsignedToSigned => { conv_data.merge_signed(target_field,
@@ -92,15 +126,85 @@ pub fn convert_field(
             orig_ds.get_boolean_field(source_field).unwrap().vec_convert())?; }
 
         ConvertType::FloatToUnsigned => { conv_data.merge_unsigned(target_field,
-            orig_ds.get_float_field(source_field).unwrap().vec_convert())?; }
+            orig_ds.get_float_values(source_field).unwrap().into_owned().vec_convert())?; }
         ConvertType::FloatToSigned => { conv_data.merge_signed(target_field,
-            orig_ds.get_float_field(source_field).unwrap().vec_convert())?; }
+            orig_ds.get_float_values(source_field).unwrap().into_owned().vec_convert())?; }
         ConvertType::FloatToText => { conv_data.merge_text(target_field,
-            orig_ds.get_float_field(source_field).unwrap().vec_convert())?; }
+            orig_ds.get_float_values(source_field).unwrap().into_owned().vec_convert())?; }
         ConvertType::FloatToBoolean => { conv_data.merge_boolean(target_field,
-            orig_ds.get_float_field(source_field).unwrap().vec_convert())?; }
+            orig_ds.get_float_values(source_field).unwrap().into_owned().vec_convert())?; }
         ConvertType::FloatToFloat => { conv_data.merge_float(target_field,
-            orig_ds.get_float_field(source_field).unwrap().vec_convert())?; }
+            orig_ds.get_float_values(source_field).unwrap().into_owned().vec_convert())?; }
+        ConvertType::FloatToDateTime => { conv_data.merge_datetime(target_field,
+            epoch_datetimes(&orig_ds.get_float_values(source_field).unwrap())?)?; }
+        ConvertType::FloatToDuration => { conv_data.merge_duration(target_field,
+            second_durations(&orig_ds.get_float_values(source_field).unwrap())?)?; }
+
+        ConvertType::UnsignedToDateTime => { conv_data.merge_datetime(target_field,
+            epoch_datetimes(&orig_ds.get_unsigned_field(source_field).unwrap().vec_convert())?)?; }
+        ConvertType::UnsignedToDuration => { conv_data.merge_duration(target_field,
+            integer_durations(orig_ds.get_unsigned_field(source_field).unwrap(),
+                |u| u.to_i64())?)?; }
+        ConvertType::SignedToDateTime => { conv_data.merge_datetime(target_field,
+            epoch_datetimes(&orig_ds.get_signed_field(source_field).unwrap().vec_convert())?)?; }
+        ConvertType::SignedToDuration => { conv_data.merge_duration(target_field,
+            integer_durations(orig_ds.get_signed_field(source_field).unwrap(), Some)?)?; }
+        ConvertType::TextToDate => { conv_data.merge_date(target_field,
+            parse_text(orig_ds.get_text_field(source_field).unwrap(),
+                |s| temporal::parse_date(s, format), temporal::epoch_date())?)?; }
+        ConvertType::TextToDateTime => { conv_data.merge_datetime(target_field,
+            parse_text(orig_ds.get_text_field(source_field).unwrap(),
+                |s| temporal::parse_datetime(s, format), temporal::epoch_datetime())?)?; }
+        ConvertType::TextToDuration => { conv_data.merge_duration(target_field,
+            parse_text(orig_ds.get_text_field(source_field).unwrap(),
+                temporal::parse_duration, Duration::zero())?)?; }
+
+        ConvertType::DateToSigned => { conv_data.merge_signed(target_field,
+            orig_ds.get_date_field(source_field).unwrap().vec_convert())?; }
+        ConvertType::DateToText => { conv_data.merge_text(target_field,
+            orig_ds.get_date_field(source_field).unwrap().iter()
+                .map(|d| temporal::format_date(d, format)).collect())?; }
+        ConvertType::DateToFloat => { conv_data.merge_float(target_field,
+            orig_ds.get_date_field(source_field).unwrap().vec_convert())?; }
+        ConvertType::DateToDate => { conv_data.merge_date(target_field,
+            orig_ds.get_date_field(source_field).unwrap().clone())?; }
+        ConvertType::DateToDateTime => { conv_data.merge_datetime(target_field,
+            orig_ds.get_date_field(source_field).unwrap().vec_convert())?; }
+
+        ConvertType::DateTimeToSigned => { conv_data.merge_signed(target_field,
+            orig_ds.get_datetime_field(source_field).unwrap().vec_convert())?; }
+        ConvertType::DateTimeToText => { conv_data.merge_text(target_field,
+            orig_ds.get_datetime_field(source_field).unwrap().iter()
+                .map(|dt| temporal::format_datetime(dt, format)).collect())?; }
+        ConvertType::DateTimeToFloat => { conv_data.merge_float(target_field,
+            orig_ds.get_datetime_field(source_field).unwrap().vec_convert())?; }
+        ConvertType::DateTimeToDate => { conv_data.merge_date(target_field,
+            orig_ds.get_datetime_field(source_field).unwrap().vec_convert())?; }
+        ConvertType::DateTimeToDateTime => { conv_data.merge_datetime(target_field,
+            orig_ds.get_datetime_field(source_field).unwrap().clone())?; }
+
+        ConvertType::DurationToSigned => { conv_data.merge_signed(target_field,
+            orig_ds.get_duration_field(source_field).unwrap().vec_convert())?; }
+        ConvertType::DurationToText => { conv_data.merge_text(target_field,
+            orig_ds.get_duration_field(source_field).unwrap().vec_convert())?; }
+        ConvertType::DurationToFloat => { conv_data.merge_float(target_field,
+            orig_ds.get_duration_field(source_field).unwrap().vec_convert())?; }
+        ConvertType::DurationToDuration => { conv_data.merge_duration(target_field,
+            orig_ds.get_duration_field(source_field).unwrap().clone())?; }
+    }
+
+    // missing values remain missing; empty strings have no non-text representation, so are
+    // missing after conversion as well
+    let nulls = if source_type == FieldType::Text && target_type != FieldType::Text {
+        let values = orig_ds.get_text_field(source_field).unwrap();
+        Some(values.iter().enumerate()
+            .map(|(row, s)| s.is_empty() || orig_ds.is_null(source_field, row))
+            .collect())
+    } else {
+        orig_ds.get_null_mask(source_field).cloned()
+    };
+    if let Some(nulls) = nulls {
+        conv_data.merge_nulls(target_field, nulls);
     }
 
     Ok(conv_data)
@@ -149,14 +253,15 @@ impl VecConvert<f64> for Vec<i64> {
 }
 
 // String -> *
+// (empty strings are converted to placeholder values, and marked as missing by convert_field)
 impl VecConvert<u64> for Vec<String> {
     fn vec_convert(&self) -> Vec<u64> {
-        self.iter().map(|s| s.parse().unwrap()).collect()
+        self.iter().map(|s| if s.is_empty() { 0 } else { s.parse().unwrap() }).collect()
     }
 }
 impl VecConvert<i64> for Vec<String> {
     fn vec_convert(&self) -> Vec<i64> {
-        self.iter().map(|s| s.parse().unwrap()).collect()
+        self.iter().map(|s| if s.is_empty() { 0 } else { s.parse().unwrap() }).collect()
     }
 }
 impl VecConvert<String> for Vec<String> {
@@ -164,12 +269,12 @@ impl VecConvert<String> for Vec<String> {
 }
 impl VecConvert<bool> for Vec<String> {
     fn vec_convert(&self) -> Vec<bool> {
-        self.iter().map(|s| s.parse().unwrap()).collect()
+        self.iter().map(|s| if s.is_empty() { false } else { s.parse().unwrap() }).collect()
     }
 }
 impl VecConvert<f64> for Vec<String> {
     fn vec_convert(&self) -> Vec<f64> {
-        self.iter().map(|s| s.parse().unwrap()).collect()
+        self.iter().map(|s| if s.is_empty() { f64::NAN } else { s.parse().unwrap() }).collect()
     }
 }
 
@@ -199,11 +304,16 @@ impl VecConvert<f64> for Vec<bool> {
 }
 
 // Float -> *
+// (NaN values, including missing value placeholders, are converted to zero)
 impl VecConvert<u64> for Vec<f64> {
-    fn vec_convert(&self) -> Vec<u64> { self.iter().map(|f| f.to_u64().unwrap()).collect() }
+    fn vec_convert(&self) -> Vec<u64> {
+        self.iter().map(|f| if f.is_nan() { 0 } else { f.to_u64().unwrap() }).collect()
+    }
 }
 impl VecConvert<i64> for Vec<f64> {
-    fn vec_convert(&self) -> Vec<i64> { self.iter().map(|f| f.to_i64().unwrap()).collect() }
+    fn vec_convert(&self) -> Vec<i64> {
+        self.iter().map(|f| if f.is_nan() { 0 } else { f.to_i64().unwrap() }).collect()
+    }
 }
 impl VecConvert<String> for Vec<f64> {
     fn vec_convert(&self) -> Vec<String> { self.iter().map(|f| format!("{}", f)).collect() }
@@ -217,9 +327,87 @@ impl VecConvert<f64> for Vec<f64> {
     fn vec_convert(&self) -> Vec<f64> { self.clone() }
 }
 
-fn gen_convert_type(source_type: FieldType, target_type: FieldType) -> ConvertType {
+// Date, DateTime, Duration -> *
+// (dates and date-times convert to seconds since the Unix epoch, durations to seconds)
+impl VecConvert<i64> for Vec<NaiveDate> {
+    fn vec_convert(&self) -> Vec<i64> { self.iter().map(temporal::date_to_epoch).collect() }
+}
+impl VecConvert<f64> for Vec<NaiveDate> {
+    fn vec_convert(&self) -> Vec<f64> {
+        self.iter().map(|d| temporal::date_to_epoch(d) as f64).collect()
+    }
+}
+impl VecConvert<NaiveDateTime> for Vec<NaiveDate> {
+    fn vec_convert(&self) -> Vec<NaiveDateTime> {
+        self.iter().map(|d| d.and_hms_opt(0, 0, 0).unwrap()).collect()
+    }
+}
+impl VecConvert<i64> for Vec<NaiveDateTime> {
+    fn vec_convert(&self) -> Vec<i64> {
+        self.iter().map(|dt| dt.and_utc().timestamp()).collect()
+    }
+}
+impl VecConvert<f64> for Vec<NaiveDateTime> {
+    fn vec_convert(&self) -> Vec<f64> { self.iter().map(temporal::datetime_to_epoch).collect() }
+}
+impl VecConvert<NaiveDate> for Vec<NaiveDateTime> {
+    fn vec_convert(&self) -> Vec<NaiveDate> { self.iter().map(|dt| dt.date()).collect() }
+}
+impl VecConvert<i64> for Vec<Duration> {
+    fn vec_convert(&self) -> Vec<i64> { self.iter().map(|d| d.num_seconds()).collect() }
+}
+impl VecConvert<f64> for Vec<Duration> {
+    fn vec_convert(&self) -> Vec<f64> { self.iter().map(temporal::duration_to_seconds).collect() }
+}
+impl VecConvert<String> for Vec<Duration> {
+    fn vec_convert(&self) -> Vec<String> { self.iter().map(temporal::format_duration).collect() }
+}
+
+/// Convert seconds since the Unix epoch to date-times (NaN values, including missing value
+/// placeholders, are converted to the Unix epoch)
+fn epoch_datetimes(values: &[f64]) -> Result<Vec<NaiveDateTime>> {
+    values.iter().map(|&f| {
+        if f.is_nan() {
+            Ok(temporal::epoch_datetime())
+        } else {
+            temporal::epoch_to_datetime(f).ok_or_else(|| Error::from_kind(
+                ErrorKind::DataFrameError(format!("timestamp {} out of range", f))))
+        }
+    }).collect()
+}
+
+/// Convert numbers of seconds to durations (NaN values, including missing value placeholders,
+/// are converted to zero durations)
+fn second_durations(values: &[f64]) -> Result<Vec<Duration>> {
+    values.iter().map(|&f| {
+        if f.is_nan() {
+            Ok(Duration::zero())
+        } else {
+            temporal::duration_from_seconds(f).ok_or_else(|| Error::from_kind(
+                ErrorKind::DataFrameError(format!("duration of {} seconds out of range", f))))
+        }
+    }).collect()
+}
+
+/// Convert integer numbers of seconds to durations
+fn integer_durations<T, F>(values: &[T], to_seconds: F) -> Result<Vec<Duration>>
+        where T: Copy + Display, F: Fn(T) -> Option<i64> {
+    values.iter().map(|&value| {
+        to_seconds(value).and_then(Duration::try_seconds).ok_or_else(|| Error::from_kind(
+            ErrorKind::DataFrameError(format!("duration of {} seconds out of range", value))))
+    }).collect()
+}
+
+/// Parse text values (empty strings, including missing values, are converted to the placeholder
+/// value)
+fn parse_text<T, F>(values: &[String], parse: F, placeholder: T) -> Result<Vec<T>>
+        where T: Clone, F: Fn(&str) -> Result<T> {
+    values.iter().map(|s| if s.is_empty() { Ok(placeholder.clone()) } else { parse(s) }).collect()
+}
+
+fn gen_convert_type(source_type: FieldType, target_type: FieldType) -> Result<ConvertType> {
     // get ready for giant match statement!
-    match source_type {
+    Ok(match source_type {
         FieldType::Unsigned => {
             match target_type {
                 FieldType::Unsigned => ConvertType::UnsignedToUnsigned,
@@ -227,6 +415,9 @@ fn gen_convert_type(source_type: FieldType, target_type: FieldType) -> ConvertTy
                 FieldType::Text     => ConvertType::UnsignedToText,
                 FieldType::Boolean  => ConvertType::UnsignedToBoolean,
                 FieldType::Float    => ConvertType::UnsignedToFloat,
+                FieldType::DateTime => ConvertType::UnsignedToDateTime,
+                FieldType::Duration => ConvertType::UnsignedToDuration,
+                _ => { return Err(unsupported_conversion(source_type, target_type)); }
             }
         },
         FieldType::Signed => {
@@ -236,6 +427,9 @@ fn gen_convert_type(source_type: FieldType, target_type: FieldType) -> ConvertTy
                 FieldType::Text     => ConvertType::SignedToText,
                 FieldType::Boolean  => ConvertType::SignedToBoolean,
                 FieldType::Float    => ConvertType::SignedToFloat,
+                FieldType::DateTime => ConvertType::SignedToDateTime,
+                FieldType::Duration => ConvertType::SignedToDuration,
+                _ => { return Err(unsupported_conversion(source_type, target_type)); }
             }
         },
         FieldType::Text => {
@@ -245,6 +439,9 @@ fn gen_convert_type(source_type: FieldType, target_type: FieldType) -> ConvertTy
                 FieldType::Text     => ConvertType::TextToText,
                 FieldType::Boolean  => ConvertType::TextToBoolean,
                 FieldType::Float    => ConvertType::TextToFloat,
+                FieldType::Date     => ConvertType::TextToDate,
+                FieldType::DateTime => ConvertType::TextToDateTime,
+                FieldType::Duration => ConvertType::TextToDuration,
             }
         },
         FieldType::Boolean => {
@@ -254,6 +451,7 @@ fn gen_convert_type(source_type: FieldType, target_type: FieldType) -> ConvertTy
                 FieldType::Text     => ConvertType::BooleanToText,
                 FieldType::Boolean  => ConvertType::BooleanToBoolean,
                 FieldType::Float    => ConvertType::BooleanToFloat,
+                _ => { return Err(unsupported_conversion(source_type, target_type)); }
             }
         },
         FieldType::Float => {
@@ -263,9 +461,46 @@ fn gen_convert_type(source_type: FieldType, target_type: FieldType) -> ConvertTy
                 FieldType::Text     => ConvertType::FloatToText,
                 FieldType::Boolean  => ConvertType::FloatToBoolean,
                 FieldType::Float    => ConvertType::FloatToFloat,
+                FieldType::DateTime => ConvertType::FloatToDateTime,
+                FieldType::Duration => ConvertType::FloatToDuration,
+                _ => { return Err(unsupported_conversion(source_type, target_type)); }
             }
         },
-    }
+        FieldType::Date => {
+            match target_type {
+                FieldType::Signed   => ConvertType::DateToSigned,
+                FieldType::Text     => ConvertType::DateToText,
+                FieldType::Float    => ConvertType::DateToFloat,
+                FieldType::Date     => ConvertType::DateToDate,
+                FieldType::DateTime => ConvertType::DateToDateTime,
+                _ => { return Err(unsupported_conversion(source_type, target_type)); }
+            }
+        },
+        FieldType::DateTime => {
+            match target_type {
+                FieldType::Signed   => ConvertType::DateTimeToSigned,
+                FieldType::Text     => ConvertType::DateTimeToText,
+                FieldType::Float    => ConvertType::DateTimeToFloat,
+                FieldType::Date     => ConvertType::DateTimeToDate,
+                FieldType::DateTime => ConvertType::DateTimeToDateTime,
+                _ => { return Err(unsupported_conversion(source_type, target_type)); }
+            }
+        },
+        FieldType::Duration => {
+            match target_type {
+                FieldType::Signed   => ConvertType::DurationToSigned,
+                FieldType::Text     => ConvertType::DurationToText,
+                FieldType::Float    => ConvertType::DurationToFloat,
+                FieldType::Duration => ConvertType::DurationToDuration,
+                _ => { return Err(unsupported_conversion(source_type, target_type)); }
+            }
+        },
+    })
+}
+
+fn unsupported_conversion(source_type: FieldType, target_type: FieldType) -> Error {
+    Error::from_kind(ErrorKind::DataConfigError(format!(
+        "transform: unsupported conversion from {:?} to {:?}", source_type, target_type)))
 }
 
 impl fmt::Debug for ConvertType {
@@ -301,6 +536,29 @@ impl fmt::Debug for ConvertType {
                 ConvertType::FloatToText        => "FloatToText",
                 ConvertType::FloatToBoolean     => "FloatToBoolean",
                 ConvertType::FloatToFloat       => "FloatToFloat",
+                ConvertType::FloatToDateTime    => "FloatToDateTime",
+                ConvertType::FloatToDuration    => "FloatToDuration",
+                ConvertType::UnsignedToDateTime => "UnsignedToDateTime",
+                ConvertType::UnsignedToDuration => "UnsignedToDuration",
+                ConvertType::SignedToDateTime   => "SignedToDateTime",
+                ConvertType::SignedToDuration   => "SignedToDuration",
+                ConvertType::TextToDate         => "TextToDate",
+                ConvertType::TextToDateTime     => "TextToDateTime",
+                ConvertType::TextToDuration     => "TextToDuration",
+                ConvertType::DateToSigned       => "DateToSigned",
+                ConvertType::DateToText         => "DateToText",
+                ConvertType::DateToFloat        => "DateToFloat",
+                ConvertType::DateToDate         => "DateToDate",
+                ConvertType::DateToDateTime     => "DateToDateTime",
+                ConvertType::DateTimeToSigned   => "DateTimeToSigned",
+                ConvertType::DateTimeToText     => "DateTimeToText",
+                ConvertType::DateTimeToFloat    => "DateTimeToFloat",
+                ConvertType::DateTimeToDate     => "DateTimeToDate",
+                ConvertType::DateTimeToDateTime => "DateTimeToDateTime",
+                ConvertType::DurationToSigned   => "DurationToSigned",
+                ConvertType::DurationToText     => "DurationToText",
+                ConvertType::DurationToFloat    => "DurationToFloat",
+                ConvertType::DurationToDuration => "DurationToDuration",
             }
         })
     }
@@ -1,5 +1,8 @@
-use std::borrow::Borrow;
-use std::io::{Read};
+use std::borrow::{Borrow, Cow};
+use std::f64;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::iter;
 use std::path::{Path};
 use std::collections::HashMap;
 
@@ -7,12 +10,23 @@ use csv;
 use encoding::{Encoding, DecoderTrap};
 use encoding::all::{ISO_8859_1, WINDOWS_1252};
 
+use chrono::{Duration, NaiveDate, NaiveDateTime};
 use matrix::Matrix;
 
 use errors::*;
 
-use dataframe::config::{self, DataConfig, SourceFile, Field, FieldType, Filter};
-use dataframe::datastore::DataStore;
+use dataframe::config::{self, DataConfig, SourceFile, SourceFormat, Field, FieldType, Filter,
+    JoinMethod, Output, OutputFormat};
+use dataframe::columnar::{self, ColumnarReader};
+use dataframe::datastore::{DataStore, FieldInfo};
+use dataframe::fitted::{FittedPipeline, FittedTransform};
+use dataframe::fixed_width::{FixedWidthColumn, FixedWidthReader};
+use dataframe::json_lines::JsonLinesReader;
+use dataframe::sparse::SparseMatrix;
+use dataframe::output::{self, CsvFormat};
+use dataframe::spreadsheet::{self, SheetRecords};
+use dataframe::sqlite;
+use dataframe::temporal;
 
 /// Primary dataframe structure
 #[derive(Debug)]
@@ -27,28 +41,106 @@ impl DataFrame {
     /// Create a new DataConfig and DataFrame from the configuration file specified
     pub fn load(config_file_path: &Path) -> Result<(DataConfig, DataFrame)> {
         let config = config::DataConfig::from_config(config_file_path)?;
-        let mut untransformed_data = DataStore::empty();
+        let untransformed_data = load_untransformed(&config)?;
+        let (df, _) = DataFrame::from_untransformed(untransformed_data, &config, None)?;
+        Ok((config, df))
+    }
+    /// Create a new DataConfig and DataFrame from the configuration file specified, fitting the
+    /// stateful transforms (normalization, scaling, one-hot vectorization and imputation) to the
+    /// loaded data. Also returns the fitted pipeline, which can be used to transform other data
+    /// consistently with this data (see `load_fitted`).
+    pub fn fit(config_file_path: &Path) -> Result<(DataConfig, DataFrame, FittedPipeline)> {
+        let config = config::DataConfig::from_config(config_file_path)?;
+        let untransformed_data = load_untransformed(&config)?;
+        let (df, fitted) = DataFrame::from_untransformed(untransformed_data, &config, None)?;
+        Ok((config, df, fitted))
+    }
+    /// Create a new DataConfig and DataFrame from the configuration file specified, applying the
+    /// parameters of a previously-fitted pipeline to the stateful transforms instead of learning
+    /// them from the loaded data. Every stateful transform must have fitted parameters.
+    pub fn load_fitted(config_file_path: &Path, fitted: &FittedPipeline)
+            -> Result<(DataConfig, DataFrame)> {
+        let config = config::DataConfig::from_config(config_file_path)?;
+        let untransformed_data = load_untransformed(&config)?;
+        let (df, _) = DataFrame::from_untransformed(untransformed_data, &config, Some(fitted))?;
+        Ok((config, df))
+    }
 
-        for source_file in &config.source_files {
-            let data_file_path = Path::new(&source_file.name[..]);
+    /// Create a new DataFrame from an already-parsed configuration, reading source data from the
+    /// provided readers instead of from disk. Readers are keyed by the `name` of the source file
+    /// they provide data for, and are decompressed according to the source file configuration.
+    pub fn from_config<R: Read>(config: &DataConfig, sources: HashMap<String, R>)
+            -> Result<DataFrame> {
+        let untransformed_data = read_untransformed(config, sources)?;
+        Ok(DataFrame::from_untransformed(untransformed_data, config, None)?.0)
+    }
+    /// Create a new DataFrame from an already-parsed configuration and source readers (as in
+    /// `from_config`), fitting the stateful transforms to the loaded data. Also returns the fitted
+    /// pipeline.
+    pub fn fit_from_config<R: Read>(config: &DataConfig, sources: HashMap<String, R>)
+            -> Result<(DataFrame, FittedPipeline)> {
+        let untransformed_data = read_untransformed(config, sources)?;
+        DataFrame::from_untransformed(untransformed_data, config, None)
+    }
+    /// Create a new DataFrame from an already-parsed configuration and source readers (as in
+    /// `from_config`), applying the parameters of a previously-fitted pipeline to the stateful
+    /// transforms.
+    pub fn from_config_fitted<R: Read>(config: &DataConfig, sources: HashMap<String, R>,
+            fitted: &FittedPipeline) -> Result<DataFrame> {
+        let untransformed_data = read_untransformed(config, sources)?;
+        Ok(DataFrame::from_untransformed(untransformed_data, config, Some(fitted))?.0)
+    }
 
-            let mut reader = csv::ReaderBuilder::new()
-                .delimiter(source_file.delimiter()?)
-                .from_path(data_file_path).chain_err(|| "error reading CSV file")?;
-            let used_fields = parse_headers(&mut reader, &source_file)?;
-            if used_fields.is_empty() {
-                return Err(Error::from_kind(ErrorKind::DataFrameError(
-                    format!("error parsing headers for file {}", source_file.name))));
-            }
-            let unt = extract_data(&mut reader, &used_fields)?;
-            untransformed_data.merge(unt)?;
-        }
-        let (transformed_data, generated_field_names) =
-            transform_data(&untransformed_data, &config)?;
+    fn from_untransformed(untransformed_data: DataStore, config: &DataConfig,
+            fitted: Option<&FittedPipeline>) -> Result<(DataFrame, FittedPipeline)> {
+        let (transformed_data, generated_field_names, fitted) =
+            transform_data(&untransformed_data, config, fitted)?;
         let mut df = DataFrame { data: DataStore::empty() };
-        df.merge_datastore(finalize_data(untransformed_data, transformed_data, &config,
+        df.merge_datastore(finalize_data(untransformed_data, transformed_data, config,
             &generated_field_names)?)?;
-        Ok((config, df))
+        df.check_label_fields(&config.label_fields())?;
+        Ok((df, fitted))
+    }
+
+    /// Create a new DataFrame of floating-point fields from the columns of a matrix (e.g. the
+    /// predictions of a model), with the specified field names. NaN values are missing.
+    pub fn from_matrix<T: Borrow<str>>(fieldnames: &[T], mat: &Matrix) -> Result<DataFrame> {
+        if fieldnames.len() != mat.ncols() {
+            return Err(Error::from_kind(ErrorKind::DataFrameError(format!(
+                "expected {} field names for matrix, found {}", mat.ncols(), fieldnames.len()))));
+        }
+        let mut data = DataStore::empty();
+        for (col, field_name) in fieldnames.iter().enumerate() {
+            let field_name = field_name.borrow().to_string();
+            let values = (0..mat.nrows()).map(|row| mat.get(row, col))
+                .collect::<::std::result::Result<Vec<f64>, _>>()
+                .map_err(|e| Error::from_kind(ErrorKind::DataFrameError(format!(
+                    "unable to read matrix: {}", e))))?;
+            let nulls = values.iter().map(|f| f.is_nan()).collect();
+            data.merge_float(&field_name, values)?;
+            data.merge_nulls(&field_name, nulls);
+        }
+        Ok(DataFrame { data })
+    }
+
+    /// Map the fields generated by the specified transforms of a configuration back to the values
+    /// of their source fields, e.g. to convert the predictions of a model trained on scaled labels
+    /// back to the units of the original labels. Normalization, scaling (using the parameters of
+    /// the fitted pipeline), one-hot vectorization (taking the category of the largest value of
+    /// each row) and one-to-one mapping transforms can be inverted. Returns a dataframe
+    /// containing the source field of each transform.
+    pub fn inverse_transform<T: Borrow<str>>(&self, config: &DataConfig,
+            fitted: &FittedPipeline, target_names: &[T]) -> Result<DataFrame> {
+        let transforms = config.transforms.as_ref().map_or(&[][..], |transforms| &transforms[..]);
+        let mut df = DataFrame { data: DataStore::empty() };
+        for target_name in target_names {
+            let target_name = target_name.borrow();
+            let transform = transforms.iter().find(|transform| transform.target_name == target_name)
+                .ok_or_else(|| Error::from_kind(ErrorKind::DataConfigError(format!(
+                    "no transform with target name {}", target_name))))?;
+            df.merge_datastore(transform.inverse(&self.data, fitted.params(target_name))?)?;
+        }
+        Ok(df)
     }
 
     fn merge_datastore(&mut self, other_ds: DataStore) -> Result<()> {
@@ -59,11 +151,37 @@ impl DataFrame {
         self.merge_datastore(other.data)
     }
 
+    /// Append the rows of another dataframe to this dataframe. Both dataframes must have the same
+    /// field names and field types.
+    pub fn append(&mut self, other: DataFrame) -> Result<()> {
+        self.data.append(other.data, false)
+    }
+    /// Append the rows of another dataframe to this dataframe, filling any fields missing from
+    /// either dataframe with missing values. Fields that exist in both dataframes must have the
+    /// same field type.
+    pub fn append_fill_missing(&mut self, other: DataFrame) -> Result<()> {
+        self.data.append(other.data, true)
+    }
+
+    /// Join this dataframe with another dataframe, matching rows on the values of the specified key
+    /// fields. Key fields must exist (with the same field types) in both dataframes, and all other
+    /// field names must be distinct between the dataframes.
+    pub fn join<T>(&self, other: &DataFrame, keys: Vec<T>, method: JoinMethod)
+            -> Result<DataFrame> where T: Borrow<str> {
+        let keys: Vec<String> = keys.iter().map(|key| key.borrow().to_string()).collect();
+        Ok(DataFrame { data: self.data.join(&other.data, &keys, method)? })
+    }
+
     /// List of the field names for this dataframe
     pub fn fieldnames(&self) -> Vec<&String> {
         self.data.fieldnames()
     }
 
+    /// Get the field type of a field in the dataframe (if exists for given field name)
+    pub fn get_field_type<T: ?Sized + Borrow<str>>(&self, field_name: &T) -> Option<FieldType> {
+        self.data.get_fieldinfo(&field_name.borrow().to_string()).map(|finfo| finfo.ty)
+    }
+
     /// Get an unsigned integer field from the dataframe (if exists for given field name)
     pub fn get_unsigned_field<T: ?Sized + Borrow<str>>(&self, field_name: &T) -> Option<&Vec<u64>> {
         self.data.get_unsigned_field(&field_name.borrow().to_string())
@@ -80,14 +198,47 @@ impl DataFrame {
     pub fn get_boolean_field<T: ?Sized + Borrow<str>>(&self, field_name: &T) -> Option<&Vec<bool>> {
         self.data.get_boolean_field(&field_name.borrow().to_string())
     }
-    /// Get an floating point field from the dataframe (if exists for given field name)
+    /// Get an floating point field from the dataframe (if exists for given field name).
+    /// Sparsely-stored fields are converted to (and kept in) dense form on first retrieval.
     pub fn get_float_field<T: ?Sized + Borrow<str>>(&self, field_name: &T) -> Option<&Vec<f64>> {
         self.data.get_float_field(&field_name.borrow().to_string())
     }
+    /// Get the values of a floating point field from the dataframe (if exists for given field
+    /// name), without keeping a dense copy of sparsely-stored fields
+    pub fn get_float_values<T: ?Sized + Borrow<str>>(&self, field_name: &T)
+            -> Option<Cow<'_, [f64]>> {
+        self.data.get_float_values(&field_name.borrow().to_string())
+    }
+    /// Get a date field from the dataframe (if exists for given field name)
+    pub fn get_date_field<T: ?Sized + Borrow<str>>(&self, field_name: &T)
+            -> Option<&Vec<NaiveDate>> {
+        self.data.get_date_field(&field_name.borrow().to_string())
+    }
+    /// Get a date-time field from the dataframe (if exists for given field name)
+    pub fn get_datetime_field<T: ?Sized + Borrow<str>>(&self, field_name: &T)
+            -> Option<&Vec<NaiveDateTime>> {
+        self.data.get_datetime_field(&field_name.borrow().to_string())
+    }
+    /// Get a duration field from the dataframe (if exists for given field name)
+    pub fn get_duration_field<T: ?Sized + Borrow<str>>(&self, field_name: &T)
+            -> Option<&Vec<Duration>> {
+        self.data.get_duration_field(&field_name.borrow().to_string())
+    }
+
+    /// Get the null mask (true for each missing value) of a field in the dataframe. Returns `None`
+    /// if the field does not exist or contains no missing values.
+    pub fn get_null_mask<T: ?Sized + Borrow<str>>(&self, field_name: &T) -> Option<&Vec<bool>> {
+        self.data.get_null_mask(field_name.borrow())
+    }
+    /// Number of missing values in a field of the dataframe
+    pub fn null_count<T: ?Sized + Borrow<str>>(&self, field_name: &T) -> usize {
+        self.get_null_mask(field_name).map_or(0, |mask| mask.iter().filter(|&&null| null).count())
+    }
 
     /// Generate a matrix from the dataframe as well as the field names for the columns of that
     /// matrix. String fields are ignored. Integer and boolean fields are transformed into floating
-    /// point numbers.
+    /// point numbers, date and date-time fields into seconds since the Unix epoch, and duration
+    /// fields into seconds. Missing values are represented as NaN.
     pub fn as_matrix(&self) -> Result<(Vec<String>, Matrix)> {
         if !self.data.is_homogeneous() {
             return Err(Error::from_kind(ErrorKind::DataFrameError(
@@ -101,30 +252,234 @@ impl DataFrame {
                 // no conversion for string fields
                 continue;
             }
-            match f.ty {
-                FieldType::Unsigned => {
-                    data_vec.append(&mut self.data.get_unsigned_field(&f.name)
-                        .expect("datastore inconsistent").iter().map(|&u| u as f64).collect());
-                },
-                FieldType::Signed   => {
-                    data_vec.append(&mut self.data.get_signed_field(&f.name)
-                        .expect("datastore inconsistent").iter().map(|&s| s as f64).collect());
-                },
-                FieldType::Boolean  => {
-                    data_vec.append(&mut self.data.get_boolean_field(&f.name)
-                        .expect("datastore inconsistent").iter()
-                        .map(|&b| if b { 1.0 } else { 0.0 }).collect());
-                },
-                FieldType::Float    => {
-                    data_vec.append(&mut self.data.get_float_field(&f.name)
-                        .expect("datastore inconsistent").clone());
-                },
-                _                   => { unreachable!() }
+            data_vec.append(&mut self.matrix_column(f));
+            fieldnames.push(f.name.clone());
+        }
+
+        let ncols = fieldnames.len();
+        Ok((fieldnames, Matrix::from_vec(data_vec, self.data.nrows(), ncols)))
+    }
+
+    /// Generate separate feature and label matrices from the dataframe, as well as the field names
+    /// for the columns of each matrix. Label columns are in the order of the specified label
+    /// fields, which must be non-string fields of the dataframe, and feature columns are all other
+    /// fields (excluding string fields). Fields are converted as in `as_matrix`.
+    pub fn as_matrix_xy<T: Borrow<str>>(&self, labels: &[T])
+            -> Result<(Vec<String>, Matrix, Vec<String>, Matrix)> {
+        let labels: Vec<&str> = labels.iter().map(|label| label.borrow()).collect();
+        if labels.is_empty() {
+            return Err(Error::from_kind(ErrorKind::DataFrameError(
+                "no label fields specified".to_string())));
+        }
+        self.check_label_fields(&labels)?;
+        if !self.data.is_homogeneous() {
+            return Err(Error::from_kind(ErrorKind::DataFrameError(
+                "DataFrame columns are not same length".to_string())));
+        }
+        let mut feature_names: Vec<String> = Vec::new();
+        let mut features: Vec<f64> = Vec::new();
+        for f in &self.data.fields {
+            if f.ty != FieldType::Text && !labels.contains(&&f.name[..]) {
+                features.append(&mut self.matrix_column(f));
+                feature_names.push(f.name.clone());
+            }
+        }
+        let mut label_names: Vec<String> = Vec::new();
+        let mut label_values: Vec<f64> = Vec::new();
+        for label in &labels {
+            let f = self.data.get_fieldinfo(&label.to_string()).expect("label fields checked");
+            label_values.append(&mut self.matrix_column(f));
+            label_names.push(f.name.clone());
+        }
+
+        let nrows = self.data.nrows();
+        let (nfeatures, nlabels) = (feature_names.len(), label_names.len());
+        Ok((feature_names, Matrix::from_vec(features, nrows, nfeatures),
+            label_names, Matrix::from_vec(label_values, nrows, nlabels)))
+    }
+
+    /// Check that label fields exist in the dataframe, are not string fields, and are not
+    /// repeated
+    fn check_label_fields(&self, labels: &[&str]) -> Result<()> {
+        for (i, label) in labels.iter().enumerate() {
+            match self.data.get_fieldinfo(&label.to_string()) {
+                Some(f) if f.ty == FieldType::Text => {
+                    return Err(Error::from_kind(ErrorKind::DataFrameError(
+                        format!("label field {} must not be a text field", label))));
+                }
+                Some(_) => {}
+                None => {
+                    return Err(Error::from_kind(ErrorKind::DataFrameError(
+                        format!("label field {} not found", label))));
+                }
+            }
+            if labels[..i].contains(label) {
+                return Err(Error::from_kind(ErrorKind::DataFrameError(
+                    format!("label field {} specified more than once", label))));
+            }
+        }
+        Ok(())
+    }
+
+    /// Values of a (non-string) field as a matrix column, with missing values as NaN
+    fn matrix_column(&self, f: &FieldInfo) -> Vec<f64> {
+        let mut values: Vec<f64> = match f.ty {
+            FieldType::Unsigned => {
+                self.data.get_unsigned_field(&f.name)
+                    .expect("datastore inconsistent").iter().map(|&u| u as f64).collect()
+            },
+            FieldType::Signed   => {
+                self.data.get_signed_field(&f.name)
+                    .expect("datastore inconsistent").iter().map(|&s| s as f64).collect()
+            },
+            FieldType::Boolean  => {
+                self.data.get_boolean_field(&f.name)
+                    .expect("datastore inconsistent").iter()
+                    .map(|&b| if b { 1.0 } else { 0.0 }).collect()
+            },
+            FieldType::Float    => {
+                self.data.get_float_values(&f.name)
+                    .expect("datastore inconsistent").into_owned()
+            },
+            FieldType::Date     => {
+                self.data.get_date_field(&f.name)
+                    .expect("datastore inconsistent").iter()
+                    .map(|d| temporal::date_to_epoch(d) as f64).collect()
+            },
+            FieldType::DateTime => {
+                self.data.get_datetime_field(&f.name)
+                    .expect("datastore inconsistent").iter()
+                    .map(temporal::datetime_to_epoch).collect()
+            },
+            FieldType::Duration => {
+                self.data.get_duration_field(&f.name)
+                    .expect("datastore inconsistent").iter()
+                    .map(temporal::duration_to_seconds).collect()
+            },
+            _                   => { unreachable!() }
+        };
+        if let Some(mask) = self.data.get_null_mask(&f.name) {
+            for (value, &null) in values.iter_mut().zip(mask) {
+                if null {
+                    *value = f64::NAN;
+                }
+            }
+        }
+        values
+    }
+
+    /// Generate a sparse (compressed sparse row) matrix from the dataframe as well as the field
+    /// names for the columns of that matrix. Fields are converted as in `as_matrix`, and only
+    /// non-zero values (including NaN for missing values) are stored. Sparsely-stored fields
+    /// (such as the outputs of vectorization transforms) are never converted to dense form.
+    pub fn as_sparse_matrix(&self) -> Result<(Vec<String>, SparseMatrix)> {
+        self.sparse_matrix_excluding(&[])
+    }
+
+    fn sparse_matrix_excluding(&self, excluded: &[&str]) -> Result<(Vec<String>, SparseMatrix)> {
+        if !self.data.is_homogeneous() {
+            return Err(Error::from_kind(ErrorKind::DataFrameError(
+                "DataFrame columns are not same length".to_string())));
+        }
+        let mut fieldnames: Vec<String> = Vec::new();
+        let mut rows: Vec<Vec<(usize, f64)>> = vec![Vec::new(); self.data.nrows()];
+
+        for f in &self.data.fields {
+            if f.ty == FieldType::Text || excluded.contains(&&f.name[..]) {
+                continue;
+            }
+            let col = fieldnames.len();
+            match self.data.get_sparse_float_field(&f.name) {
+                Some(sparse) if sparse.default_value() == 0.0 => {
+                    let mask = self.data.get_null_mask(&f.name);
+                    let mut entries = sparse.entries().iter().peekable();
+                    for (row, values) in rows.iter_mut().enumerate() {
+                        let value = match entries.peek() {
+                            Some(&&(entry_row, value)) if entry_row == row => {
+                                entries.next();
+                                value
+                            }
+                            _ => 0.0,
+                        };
+                        if mask.is_some_and(|mask| mask[row]) {
+                            values.push((col, f64::NAN));
+                        } else if value != 0.0 {
+                            values.push((col, value));
+                        }
+                    }
+                }
+                _ => {
+                    for (values, value) in rows.iter_mut().zip(self.matrix_column(f)) {
+                        if value != 0.0 {
+                            values.push((col, value));
+                        }
+                    }
+                }
             }
             fieldnames.push(f.name.clone());
         }
 
-        Ok((fieldnames, Matrix::from_vec(data_vec, self.data.nrows(), self.data.fields.len())))
+        let ncols = fieldnames.len();
+        Ok((fieldnames, SparseMatrix::from_rows(rows, ncols)))
+    }
+
+    /// Write the dataframe as LibSVM-format text, with one line per row: the value of the
+    /// specified (non-string) label field, followed by the 1-based column indices and values of
+    /// the non-zero features of the sparse matrix of all other fields (see `as_sparse_matrix`).
+    /// Missing feature values are omitted; missing label values are an error.
+    pub fn write_libsvm<W: Write>(&self, writer: W, label: &str) -> Result<()> {
+        self.check_label_fields(&[label])?;
+        let label_info = self.data.get_fieldinfo(&label.to_string()).expect("label field checked");
+        let (_, features) = self.sparse_matrix_excluding(&[label])?;
+        output::write_libsvm(&self.matrix_column(label_info), &features, writer)
+    }
+
+    /// Write all fields of the dataframe (in field order) as delimited text, in the specified
+    /// format
+    pub fn write_csv<W: Write>(&self, writer: W, format: &CsvFormat) -> Result<()> {
+        output::write_csv(&self.data, writer, format)
+    }
+
+    /// Write all fields of the dataframe (in field order) as a Parquet file. Missing values are
+    /// written as nulls, and durations (which Parquet has no type for) as seconds.
+    pub fn write_parquet<W: Write + Send>(&self, writer: W) -> Result<()> {
+        columnar::write_parquet(&self.data, writer)
+    }
+
+    /// Write all fields of the dataframe (in field order) as an Arrow IPC file. Missing values are
+    /// written as nulls.
+    pub fn write_arrow_ipc<W: Write>(&self, writer: W) -> Result<()> {
+        columnar::write_arrow_ipc(&self.data, writer)
+    }
+
+    /// Write the dataframe as a new table of the SQLite database at the specified path (creating
+    /// the database if it doesn't exist). Dates and date-times are stored as ISO 8601 text,
+    /// booleans as integers, durations as seconds, and missing values as NULL.
+    pub fn write_sqlite(&self, path: &Path, table: &str) -> Result<()> {
+        sqlite::write_table(&self.data, path, table)
+    }
+
+    /// Write the dataframe to the output file specified in a configuration
+    pub fn write_output(&self, output: &Output) -> Result<()> {
+        let create = || File::create(output.path()).map(BufWriter::new).chain_err(|| format!(
+            "unable to create output file {}", output.name));
+        match output.format() {
+            OutputFormat::Delimited => self.write_csv(create()?, &output.csv_format()?),
+            OutputFormat::Parquet   => self.write_parquet(create()?),
+            OutputFormat::ArrowIpc  => self.write_arrow_ipc(create()?),
+            OutputFormat::Sqlite    => {
+                let table = output.table.as_ref().ok_or_else(|| Error::from_kind(
+                    ErrorKind::DataConfigError(format!("SQLite output {} requires a table",
+                        output.name))))?;
+                self.write_sqlite(output.path(), table)
+            }
+            OutputFormat::LibSvm    => {
+                let label = output.label.as_ref().ok_or_else(|| Error::from_kind(
+                    ErrorKind::DataConfigError(format!("LibSVM output {} requires a label field",
+                        output.name))))?;
+                self.write_libsvm(create()?, label)
+            }
+        }.chain_err(|| format!("error writing output file {}", output.name))
     }
 
     /// Generate a sub-dataframe consisting of the columns specified
@@ -159,8 +514,32 @@ impl DataFrame {
                         }
                     },
                     FieldType::Float => {
-                        match self.data.get_float_field(&field_name) {
-                            Some(v) => { subds.merge_float(&field_name, v.clone())?; Some(()) },
+                        match self.data.get_sparse_float_field(&field_name) {
+                            Some(v) => {
+                                subds.merge_sparse_float(&field_name, v.clone())?;
+                                Some(())
+                            },
+                            None    => match self.data.get_float_field(&field_name) {
+                                Some(v) => { subds.merge_float(&field_name, v.clone())?; Some(()) },
+                                None    => None
+                            }
+                        }
+                    },
+                    FieldType::Date => {
+                        match self.data.get_date_field(&field_name) {
+                            Some(v) => { subds.merge_date(&field_name, v.clone())?; Some(()) },
+                            None    => None
+                        }
+                    },
+                    FieldType::DateTime => {
+                        match self.data.get_datetime_field(&field_name) {
+                            Some(v) => { subds.merge_datetime(&field_name, v.clone())?; Some(()) },
+                            None    => None
+                        }
+                    },
+                    FieldType::Duration => {
+                        match self.data.get_duration_field(&field_name) {
+                            Some(v) => { subds.merge_duration(&field_name, v.clone())?; Some(()) },
                             None    => None
                         }
                     },
@@ -169,6 +548,9 @@ impl DataFrame {
                     return Err(Error::from_kind(ErrorKind::DataFrameError(
                         "Datastore inconsistent".to_string())));
                 }
+                if let Some(mask) = self.data.get_null_mask(&field_name) {
+                    subds.merge_nulls(&field_name, mask.clone());
+                }
             } else {
                 return Err(Error::from_kind(ErrorKind::DataFrameError(
                     format!("Unknown field name: {}", field_name))));
@@ -178,20 +560,215 @@ impl DataFrame {
     }
 }
 
-fn parse_headers<'a, R>(reader: &mut csv::Reader<R>, source_file: &'a SourceFile)
-        -> Result<Vec<FieldSled<'a>>> where R: Read {
-    let headers = reader.headers().chain_err(|| "unable to parse CSV headers")?;
-    let mut field_sleds = vec!();
-    let mut filter_map: HashMap<String, &'a Filter> = HashMap::new();
+/// Combine the data from a source file with the data from the preceding source files, either by
+/// key-based join (if specified) or positionally
+fn combine_source(mut data: DataStore, source_file: &SourceFile, source_data: DataStore)
+        -> Result<DataStore> {
+    match source_file.join {
+        Some(ref join) => {
+            data.join(&source_data, &join.keys, join.method).chain_err(|| format!(
+                "error joining source file {}", source_file.name))
+        }
+        None => {
+            data.merge(source_data)?;
+            Ok(data)
+        }
+    }
+}
+
+fn add_filename_field(data: &mut DataStore, field_name: &str, filename: String) {
+    for _ in 0..data.nrows() {
+        data.insert_text(field_name.to_string(), filename.clone());
+    }
+}
+
+/// Load the (untransformed) data of each source file of a configuration from disk
+fn load_untransformed(config: &DataConfig) -> Result<DataStore> {
+    let mut untransformed_data = DataStore::empty();
+    for source_file in &config.source_files {
+        // append together the data from each file matching this source file
+        let mut source_data = DataStore::empty();
+        for path in source_file.paths()? {
+            // SQLite databases are queried in place rather than read as a stream
+            let file_data = match source_file.format() {
+                SourceFormat::Sqlite => load_sqlite(source_file, &path),
+                _ => load_source(source_file, source_file.open(&path)?),
+            };
+            let mut file_data = file_data
+                .chain_err(|| format!("error loading file {}", path.display()))?;
+            if let Some(ref filename_field) = source_file.filename_field {
+                let filename = path.file_name().map_or(source_file.name.clone(),
+                    |filename| filename.to_string_lossy().into_owned());
+                add_filename_field(&mut file_data, filename_field, filename);
+            }
+            source_data.append(file_data, false)?;
+        }
+        untransformed_data = combine_source(untransformed_data, source_file, source_data)?;
+    }
+    Ok(untransformed_data)
+}
+
+/// Load the (untransformed) data of each source file of a configuration from the provided readers
+fn read_untransformed<R: Read>(config: &DataConfig, mut sources: HashMap<String, R>)
+        -> Result<DataStore> {
+    config.validate()?;
+    let mut untransformed_data = DataStore::empty();
+
+    for source_file in &config.source_files {
+        let reader = sources.remove(&source_file.name).ok_or_else(|| Error::from_kind(
+            ErrorKind::DataFrameError(format!("no source provided for source file {}",
+                source_file.name))))?;
+        let mut source_data = load_source(source_file, source_file.decompress(reader)?)?;
+        if let Some(ref filename_field) = source_file.filename_field {
+            add_filename_field(&mut source_data, filename_field, source_file.name.clone());
+        }
+        untransformed_data = combine_source(untransformed_data, source_file, source_data)?;
+    }
+    Ok(untransformed_data)
+}
+
+fn load_source<R: Read>(source_file: &SourceFile, reader: R) -> Result<DataStore> {
+    match source_file.format() {
+        SourceFormat::Delimited   => load_delimited(source_file, reader),
+        SourceFormat::FixedWidth  => load_fixed_width(source_file, reader),
+        SourceFormat::JsonLines   => load_json_lines(source_file, reader),
+        SourceFormat::Spreadsheet => load_spreadsheet(source_file, reader),
+        SourceFormat::Parquet     => {
+            extract_data(ColumnarReader::parquet(reader, &source_file.fields)?,
+                &ordered_field_sleds(source_file))
+        }
+        SourceFormat::ArrowIpc    => {
+            extract_data(ColumnarReader::arrow_ipc(reader, &source_file.fields)?,
+                &ordered_field_sleds(source_file))
+        }
+        SourceFormat::Sqlite      => {
+            let records = sqlite::query_reader_records(reader, sqlite_query(source_file)?,
+                &source_file.fields)?;
+            extract_data(records.into_iter().map(Ok), &ordered_field_sleds(source_file))
+        }
+    }
+}
+
+fn load_sqlite(source_file: &SourceFile, path: &Path) -> Result<DataStore> {
+    let records = sqlite::query_records(path, sqlite_query(source_file)?, &source_file.fields)?;
+    extract_data(records.into_iter().map(Ok), &ordered_field_sleds(source_file))
+}
+
+fn sqlite_query(source_file: &SourceFile) -> Result<&str> {
+    source_file.query.as_ref().map(|query| &query[..]).ok_or_else(|| Error::from_kind(
+        ErrorKind::DataConfigError(format!("SQLite source file {} requires a query",
+            source_file.name))))
+}
+
+fn load_delimited<R: Read>(source_file: &SourceFile, reader: R) -> Result<DataStore> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(source_file.delimiter()?)
+        .has_headers(source_file.has_headers())
+        .from_reader(reader);
+    let headers = reader.headers().chain_err(|| "unable to parse CSV headers")?.clone();
+    let used_fields = parse_headers(&headers, source_file)?;
+    if used_fields.is_empty() {
+        return Err(Error::from_kind(ErrorKind::DataFrameError(
+            format!("error parsing headers for file {}", source_file.name))));
+    }
+    extract_data(reader.byte_records().map(|row| row.chain_err(|| "error reading CSV record")),
+        &used_fields)
+}
+
+fn load_spreadsheet<R: Read>(source_file: &SourceFile, reader: R) -> Result<DataStore> {
+    let range = spreadsheet::read_sheet(reader, source_file.sheet.as_ref().map(|s| &s[..]))
+        .chain_err(|| format!("error reading spreadsheet {}", source_file.name))?;
+    let mut records = SheetRecords::new(&range, source_file.header_row);
+    let first_record = match records.next() {
+        Some(record) => record?,
+        None         => csv::ByteRecord::new()
+    };
+    let headers = csv::StringRecord::from_byte_record(first_record.clone())
+        .chain_err(|| "unable to parse spreadsheet headers")?;
+    let used_fields = parse_headers(&headers, source_file)?;
+    if used_fields.is_empty() {
+        return Err(Error::from_kind(ErrorKind::DataFrameError(
+            format!("error parsing headers for file {}", source_file.name))));
+    }
+    if source_file.has_headers() {
+        extract_data(records, &used_fields)
+    } else {
+        extract_data(iter::once(Ok(first_record)).chain(records), &used_fields)
+    }
+}
+
+fn load_fixed_width<R: Read>(source_file: &SourceFile, reader: R) -> Result<DataStore> {
+    let columns = source_file.fields.iter().map(|field| {
+        // start and width existence checked during config validation
+        FixedWidthColumn {
+            start: field.start.unwrap_or(0),
+            width: field.width.unwrap_or(0),
+            trim: field.trim(),
+        }
+    }).collect();
+    let mut reader = FixedWidthReader::new(BufReader::new(reader), columns);
+    if source_file.has_headers() {
+        // header line provides no information for fixed-width files
+        if let Some(header) = reader.next() {
+            header?;
+        }
+    }
+    extract_data(reader, &ordered_field_sleds(source_file))
+}
+
+fn load_json_lines<R: Read>(source_file: &SourceFile, reader: R) -> Result<DataStore> {
+    let paths = source_file.fields.iter().map(|field| field.source_name.clone()).collect();
+    let reader = JsonLinesReader::new(BufReader::new(reader), paths);
+    extract_data(reader, &ordered_field_sleds(source_file))
+}
+
+/// Field sleds for readers which produce records containing fields in the same order as the
+/// source file field list
+fn ordered_field_sleds<'a>(source_file: &'a SourceFile) -> Vec<FieldSled<'a>> {
+    let filter_map = source_filters(source_file);
+    source_file.fields.iter().enumerate().map(|(i, field)| {
+        FieldSled::new(field, field_filters(&filter_map, field), i)
+    }).collect()
+}
+
+/// Filters of a source file, keyed by source field name (a field may have multiple filters, all
+/// of which must pass)
+fn source_filters(source_file: &SourceFile) -> HashMap<String, Vec<&Filter>> {
+    let mut filter_map: HashMap<String, Vec<&Filter>> = HashMap::new();
     if let Some(ref filters) = source_file.filters {
         for filter in filters {
-            filter_map.insert(filter.source_field.clone(), &filter);
+            filter_map.entry(filter.source_field.clone()).or_default().push(filter);
         }
     }
-    for (i, field_name) in headers.iter().enumerate() {
-        if let Some(field) = source_file.get_source_field(&field_name.to_string()) {
-            field_sleds.push(FieldSled::new(field,
-                filter_map.get(&field.source_name).map(|&filter| filter) ,i));
+    filter_map
+}
+
+fn field_filters<'a>(filter_map: &HashMap<String, Vec<&'a Filter>>, field: &Field)
+        -> Vec<&'a Filter> {
+    filter_map.get(&field.source_name).cloned().unwrap_or_default()
+}
+
+fn parse_headers<'a>(headers: &csv::StringRecord, source_file: &'a SourceFile)
+        -> Result<Vec<FieldSled<'a>>> {
+    let mut field_sleds = vec!();
+    let filter_map = source_filters(source_file);
+    if source_file.has_headers() {
+        for (i, field_name) in headers.iter().enumerate() {
+            if let Some(field) = source_file.get_source_field(&field_name.to_string()) {
+                field_sleds.push(FieldSled::new(field, field_filters(&filter_map, field), i));
+            }
+        }
+    } else {
+        // without a header row, the first record is used to determine the record width
+        for field in &source_file.fields {
+            let index = field.source_index.ok_or(Error::from_kind(ErrorKind::DataConfigError(
+                format!("field {} missing source_index", field.source_name))))?;
+            if index >= headers.len() {
+                return Err(Error::from_kind(ErrorKind::DataConfigError(
+                    format!("source_index {} of field {} exceeds record width {}", index,
+                        field.source_name, headers.len()))));
+            }
+            field_sleds.push(FieldSled::new(field, field_filters(&filter_map, field), index));
         }
     }
     Ok(field_sleds)
@@ -214,37 +791,46 @@ fn decode(bytes: &[u8], linenum: usize, fieldnum: usize) -> Result<String> {
 
 struct FieldSled<'a> {
     field: &'a Field,
-    filter: Option<&'a Filter>,
+    filters: Vec<&'a Filter>,
     index: usize,
 }
 impl<'a> FieldSled<'a> {
-    fn new(field: &'a Field, filter: Option<&'a Filter>, index: usize) -> FieldSled<'a> {
+    fn new(field: &'a Field, filters: Vec<&'a Filter>, index: usize) -> FieldSled<'a> {
         FieldSled {
             field: field,
-            filter: filter,
+            filters: filters,
             index: index,
         }
     }
 }
 
-fn extract_data<R>(reader: &mut csv::Reader<R>, field_sleds: &Vec<FieldSled>)
-        -> Result<DataStore> where R: Read {
+fn extract_data<I>(records: I, field_sleds: &[FieldSled]) -> Result<DataStore>
+        where I: Iterator<Item=Result<csv::ByteRecord>> {
     let mut data = DataStore::empty();
-    for (rownum, row) in reader.byte_records().enumerate() {
-        let record = row.chain_err(|| format!("error reading file line {}", rownum + 2))?;
+    for (rownum, row) in records.enumerate() {
+        let record = row.chain_err(|| format!("error reading record {}", rownum + 1))?;
 
         // TODO: see if this could be sped up by storing decoded field in HashMap
 
         // loop through once to check filters
         let mut use_record = true;
-        for sled in field_sleds {
-            if let Some(ref filter) = sled.filter {
-                let decoded_field = decode(record.get(sled.index).ok_or(ErrorKind::DataFrameError(
-                        "field index out of bounds".to_string()))?, rownum + 1, sled.index)?;
-                if !filter.apply(&decoded_field)? {
+        'sleds: for sled in field_sleds {
+            if sled.filters.is_empty() {
+                continue;
+            }
+            let decoded_field = decode(record.get(sled.index).ok_or(ErrorKind::DataFrameError(
+                    "field index out of bounds".to_string()))?, rownum + 1, sled.index)?;
+            let is_null = sled.field.is_null_value(&decoded_field);
+            for filter in &sled.filters {
+                let keep = if is_null {
+                    filter.apply_null()
+                } else {
+                    filter.apply_formatted(&decoded_field, sled.field.format())?
+                };
+                if !keep {
                     // move on to next record
                     use_record = false;
-                    break;
+                    break 'sleds;
                 }
             }
         }
@@ -255,11 +841,16 @@ fn extract_data<R>(reader: &mut csv::Reader<R>, field_sleds: &Vec<FieldSled>)
                 let decoded_field = decode(record.get(sled.index).ok_or(ErrorKind::DataFrameError(
                         "field index out of bounds".to_string()))?, rownum + 1, sled.index)?;
 
-                data.insert(
-                    sled.field.target_name().clone(),
-                    sled.field.field_type,
-                    decoded_field
-                ).chain_err(|| "data insertion error")?;
+                if sled.field.is_null_value(&decoded_field) {
+                    data.insert_null(sled.field.target_name().clone(), sled.field.field_type);
+                } else {
+                    data.insert_formatted(
+                        sled.field.target_name().clone(),
+                        sled.field.field_type,
+                        decoded_field,
+                        sled.field.format()
+                    ).chain_err(|| "data insertion error")?;
+                }
             }
         }
     }
@@ -270,12 +861,16 @@ fn extract_data<R>(reader: &mut csv::Reader<R>, field_sleds: &Vec<FieldSled>)
     Ok(data)
 }
 
-fn transform_data<'a>(untransformed_data: &DataStore, config: &DataConfig)
-        -> Result<(DataStore, Vec<Vec<String>>)> {
+/// Perform the transforms of a configuration. Stateful transforms use the parameters of the
+/// fitted pipeline if provided, and are otherwise fitted to the data; returns the transformed data,
+/// the field names generated by each transform, and the fitted parameters used.
+fn transform_data(untransformed_data: &DataStore, config: &DataConfig,
+        fitted: Option<&FittedPipeline>) -> Result<(DataStore, Vec<Vec<String>>, FittedPipeline)> {
 
     if let Some(ref transforms) = config.transforms {
         let mut tf_data = DataStore::empty();
         let mut generated_field_names: Vec<Vec<String>> = vec![Vec::new(); transforms.len()];
+        let mut fitted_transforms: Vec<Option<FittedTransform>> = vec![None; transforms.len()];
 
         let mut work: Vec<usize> = Vec::new();
         for i in 0..transforms.len() { work.push(i); }
@@ -285,23 +880,33 @@ fn transform_data<'a>(untransformed_data: &DataStore, config: &DataConfig)
             let mut anything_done_this_loop = false;
             while let Some(index) = work.pop() {
                 let transform = &transforms[index];
-                if transform.source_exists(untransformed_data) {
-                    let transformed_data = transform.transform(untransformed_data)?;
-                    generated_field_names[index] = transformed_data.fieldnames()
-                        .iter().map(|&s| s.clone()).collect();
-                    tf_data.merge_fields(transformed_data.fieldnames(), &transform.target_type(),
-                        &transformed_data)?;
-                    anything_done_this_loop = true;
+                let source_data = if transform.source_exists(untransformed_data) {
+                    untransformed_data
                 } else if transform.source_exists(&tf_data) {
-                    let transformed_data = transform.transform(&tf_data)?;
-                    generated_field_names[index] = transformed_data.fieldnames()
-                        .iter().map(|&s| s.clone()).collect();
-                    tf_data.merge_fields(transformed_data.fieldnames(), &transform.target_type(),
-                        &transformed_data)?;
-                    anything_done_this_loop = true;
+                    &tf_data
                 } else {
                     more_work.push(index);
-                }
+                    continue;
+                };
+                let params = match fitted {
+                    Some(fitted) if transform.is_stateful() => {
+                        Some(fitted.params(&transform.target_name).ok_or_else(|| {
+                            Error::from_kind(ErrorKind::DataConfigError(format!(
+                                "no fitted parameters for transform {}",
+                                transform.target_name)))
+                        })?)
+                    }
+                    _ => None,
+                };
+                let (transformed_data, params) = transform.transform_fitted(source_data, params)?;
+                generated_field_names[index] = transformed_data.fieldnames()
+                    .iter().map(|&s| s.clone()).collect();
+                fitted_transforms[index] = params.map(|params| FittedTransform {
+                    target_name: transform.target_name.clone(),
+                    params,
+                });
+                tf_data.merge(transformed_data)?;
+                anything_done_this_loop = true;
             }
 
             if !anything_done_this_loop {
@@ -312,9 +917,12 @@ fn transform_data<'a>(untransformed_data: &DataStore, config: &DataConfig)
             }
             work.append(&mut more_work);
         }
-        Ok((tf_data, generated_field_names))
+        let fitted = FittedPipeline {
+            transforms: fitted_transforms.into_iter().flatten().collect(),
+        };
+        Ok((tf_data, generated_field_names, fitted))
     } else {
-        Ok((DataStore::empty(), Vec::new()))
+        Ok((DataStore::empty(), Vec::new(), FittedPipeline::default()))
     }
 }
 
@@ -323,18 +931,24 @@ fn finalize_data(untransformed_data: DataStore, transformed_data: DataStore, con
     let mut finalized_data = DataStore::empty();
     for source_file in &config.source_files {
         for field in &source_file.fields {
-            if field.add_to_frame() {
+            // join key fields are shared between source files, so only add them once
+            if field.add_to_frame() && !finalized_data.field_map.contains_key(field.target_name()) {
                 finalized_data.merge_field(field.target_name(), &field.field_type,
                     &untransformed_data)?;
             }
         }
+        if let Some(ref filename_field) = source_file.filename_field {
+            finalized_data.merge_field(filename_field, &FieldType::Text, &untransformed_data)?;
+        }
     }
     if let Some(ref transforms) = config.transforms {
         for (i, transform) in transforms.iter().enumerate() {
             if transform.add_to_frame() {
-                finalized_data.merge_fields(
-                    generated_field_names[i].iter().map(|&ref s| s).collect(),
-                    &transform.target_type(), &transformed_data)?;
+                for field_name in &generated_field_names[i] {
+                    let field_type = transformed_data.get_fieldinfo(field_name)
+                        .expect("datastore inconsistent").ty;
+                    finalized_data.merge_field(field_name, &field_type, &transformed_data)?;
+                }
             }
         }
     }
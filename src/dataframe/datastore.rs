@@ -1,10 +1,16 @@
+use std::borrow::Cow;
 use std::cmp::max;
+use std::f64;
 use std::collections::HashMap;
 use std::hash::Hash;
 
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+
 use errors::*;
 
-use dataframe::config::FieldType;
+use dataframe::config::{FieldType, JoinMethod};
+use dataframe::sparse::SparseVec;
+use dataframe::temporal;
 
 /// Field information for a field within a data store
 #[derive(Debug, Clone)]
@@ -45,6 +51,19 @@ pub struct DataStore {
     pub boolean: HashMap<String, Vec<bool>>,
     /// Storage for floating-point numbers
     pub float: HashMap<String, Vec<f64>>,
+    /// Storage for floating-point numbers stored sparsely (such as vectorized fields)
+    pub sparse: HashMap<String, SparseVec>,
+    /// Storage for dates
+    pub date: HashMap<String, Vec<NaiveDate>>,
+    /// Storage for date-times
+    pub datetime: HashMap<String, Vec<NaiveDateTime>>,
+    /// Storage for durations
+    pub duration: HashMap<String, Vec<Duration>>,
+
+    /// Null masks for fields containing missing values (true for each missing value). Fields
+    /// without any missing values have no mask. The storage of a missing value holds a
+    /// placeholder value (zero, empty string, false, NaN, or the Unix epoch).
+    pub nulls: HashMap<String, Vec<bool>>,
 }
 fn max_len<K, T>(h: &HashMap<K, Vec<T>>) -> usize where K: Eq + Hash {
     h.values().fold(0, |acc, v| max(acc, v.len()))
@@ -72,13 +91,86 @@ fn is_hm_homogeneous_with<K, T>(h: &HashMap<K, Vec<T>>, value: usize) -> Option<
         } else { None }
     })
 }
-fn insert_value<T>(h: &mut HashMap<String, Vec<T>>, k: String, v: T) {
-    if h.contains_key(&k) {
-        h.get_mut(&k).unwrap().push(v);
+fn is_sparse_homogeneous_with(h: &HashMap<String, SparseVec>, value: usize) -> Option<usize> {
+    let mut lens = h.values().map(|v| v.len());
+    match lens.next() {
+        None => Some(value),
+        Some(len) if lens.all(|l| l == len) => {
+            if len == 0 {
+                Some(value)
+            } else if value == 0 || len == value {
+                Some(len)
+            } else { None }
+        }
+        Some(_) => None,
+    }
+}
+fn insert_value<T>(h: &mut HashMap<String, Vec<T>>, k: String, v: T) -> usize {
+    let values = h.entry(k).or_default();
+    values.push(v);
+    values.len()
+}
+fn append_values<T>(dest: &mut HashMap<String, Vec<T>>, src: &mut HashMap<String, Vec<T>>,
+        k: String) {
+    let mut values = src.remove(&k).unwrap_or_default();
+    dest.entry(k).or_default().append(&mut values);
+}
+fn extend_values<T: Clone>(h: &mut HashMap<String, Vec<T>>, k: &str, v: T, n: usize) {
+    let values = h.entry(k.to_string()).or_default();
+    let len = values.len();
+    values.resize(len + n, v);
+}
+/// Value of a join key field in a single row
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum KeyValue<'a> {
+    Unsigned(u64),
+    Signed(i64),
+    Text(&'a str),
+    Boolean(bool),
+    /// Bit pattern of a (non-NaN) floating-point value, with negative zero replaced by zero
+    Float(u64),
+    Date(NaiveDate),
+    DateTime(NaiveDateTime),
+    Duration(Duration),
+}
+fn float_key<'a>(f: f64) -> Option<KeyValue<'a>> {
+    // NaN isn't equal to anything, so never matches
+    if f.is_nan() {
+        None
+    } else if f == 0.0 {
+        Some(KeyValue::Float(0.0f64.to_bits()))
     } else {
-        h.insert(k, vec!(v));
+        Some(KeyValue::Float(f.to_bits()))
     }
 }
+fn append_keys<'a, I>(row_keys: &mut [Option<Vec<KeyValue<'a>>>], values: I)
+        where I: Iterator<Item = Option<KeyValue<'a>>> {
+    for (row_key, value) in row_keys.iter_mut().zip(values) {
+        match value {
+            Some(value) => { if let Some(row_key) = row_key.as_mut() { row_key.push(value); } }
+            None => { *row_key = None; }
+        }
+    }
+}
+fn gather_values<T: Clone>(values: &[T], rows: &[Option<usize>], default: T) -> Vec<T> {
+    rows.iter().map(|row| row.map_or(default.clone(), |i| values[i].clone())).collect()
+}
+fn gather_nulls(mask: Option<&Vec<bool>>, rows: &[Option<usize>]) -> Vec<bool> {
+    rows.iter().map(|row| match *row {
+        Some(i) => mask.is_some_and(|mask| mask[i]),
+        None    => true,
+    }).collect()
+}
+fn gather_key_values<T: Clone>(left: &[T], right: &[T], left_rows: &[Option<usize>],
+        right_rows: &[Option<usize>]) -> Vec<T> {
+    left_rows.iter().zip(right_rows).map(|(&l, &r)| {
+        match (l, r) {
+            (Some(i), _)    => left[i].clone(),
+            (None, Some(j)) => right[j].clone(),
+            (None, None)    => unreachable!(),
+        }
+    }).collect()
+}
 impl DataStore {
     /// Generate and return an empty data store
     pub fn empty() -> DataStore {
@@ -91,6 +183,12 @@ impl DataStore {
             text: HashMap::new(),
             boolean: HashMap::new(),
             float: HashMap::new(),
+            sparse: HashMap::new(),
+            date: HashMap::new(),
+            datetime: HashMap::new(),
+            duration: HashMap::new(),
+
+            nulls: HashMap::new(),
         }
     }
 
@@ -104,32 +202,86 @@ impl DataStore {
     /// Insert an unsigned integer with provided field name
     pub fn insert_unsigned(&mut self, field_name: String, value: u64) {
         self.add_field(field_name.clone(), FieldType::Unsigned);
+        self.mark_present(&field_name);
         insert_value(&mut self.unsigned, field_name, value);
     }
     /// Insert a signed integer with provided field name
     pub fn insert_signed(&mut self, field_name: String, value: i64) {
         self.add_field(field_name.clone(), FieldType::Signed);
+        self.mark_present(&field_name);
         insert_value(&mut self.signed, field_name, value);
     }
     /// Insert a string with provided field name
     pub fn insert_text(&mut self, field_name: String, value: String) {
         self.add_field(field_name.clone(), FieldType::Text);
+        self.mark_present(&field_name);
         insert_value(&mut self.text, field_name, value);
     }
     /// Insert a boolean with provided field name
     pub fn insert_boolean(&mut self, field_name: String, value: bool) {
         self.add_field(field_name.clone(), FieldType::Boolean);
+        self.mark_present(&field_name);
         insert_value(&mut self.boolean, field_name, value);
     }
     /// Insert a floating-point number with provided field name
     pub fn insert_float(&mut self, field_name: String, value: f64) {
         self.add_field(field_name.clone(), FieldType::Float);
+        self.mark_present(&field_name);
         insert_value(&mut self.float, field_name, value);
     }
+    /// Insert a date with provided field name
+    pub fn insert_date(&mut self, field_name: String, value: NaiveDate) {
+        self.add_field(field_name.clone(), FieldType::Date);
+        self.mark_present(&field_name);
+        insert_value(&mut self.date, field_name, value);
+    }
+    /// Insert a date-time with provided field name
+    pub fn insert_datetime(&mut self, field_name: String, value: NaiveDateTime) {
+        self.add_field(field_name.clone(), FieldType::DateTime);
+        self.mark_present(&field_name);
+        insert_value(&mut self.datetime, field_name, value);
+    }
+    /// Insert a duration with provided field name
+    pub fn insert_duration(&mut self, field_name: String, value: Duration) {
+        self.add_field(field_name.clone(), FieldType::Duration);
+        self.mark_present(&field_name);
+        insert_value(&mut self.duration, field_name, value);
+    }
+
+    /// Insert a missing value of given field type with specified field name
+    pub fn insert_null(&mut self, field_name: String, field_type: FieldType) {
+        self.add_field(field_name.clone(), field_type);
+        let len = match field_type {
+            FieldType::Unsigned => insert_value(&mut self.unsigned, field_name.clone(), 0),
+            FieldType::Signed   => insert_value(&mut self.signed, field_name.clone(), 0),
+            FieldType::Text     => insert_value(&mut self.text, field_name.clone(), String::new()),
+            FieldType::Boolean  => insert_value(&mut self.boolean, field_name.clone(), false),
+            FieldType::Float    => insert_value(&mut self.float, field_name.clone(), f64::NAN),
+            FieldType::Date     => insert_value(&mut self.date, field_name.clone(),
+                temporal::epoch_date()),
+            FieldType::DateTime => insert_value(&mut self.datetime, field_name.clone(),
+                temporal::epoch_datetime()),
+            FieldType::Duration => insert_value(&mut self.duration, field_name.clone(),
+                Duration::zero()),
+        };
+        let mask = self.nulls.entry(field_name).or_insert_with(|| vec![false; len - 1]);
+        mask.push(true);
+    }
+    fn mark_present(&mut self, field_name: &str) {
+        if let Some(mask) = self.nulls.get_mut(field_name) {
+            mask.push(false);
+        }
+    }
 
     /// Insert a value (in unparsed string form) of given field type with specified field name
     pub fn insert(&mut self, field_name: String, field_type: FieldType, value_str: String)
             -> Result<()> {
+        self.insert_formatted(field_name, field_type, value_str, None)
+    }
+    /// Insert a value (in unparsed string form) of given field type with specified field name,
+    /// parsing date and date-time values with the specified format (or ISO 8601 if none)
+    pub fn insert_formatted(&mut self, field_name: String, field_type: FieldType,
+            value_str: String, format: Option<&str>) -> Result<()> {
         match field_type {
             FieldType::Unsigned => self.insert_unsigned(field_name,
                 value_str.parse().chain_err(|| "unsigned integer parse error")?),
@@ -140,6 +292,12 @@ impl DataStore {
                 value_str.parse().chain_err(|| "boolean parse error")?),
             FieldType::Float    => self.insert_float(field_name,
                 value_str.parse().chain_err(|| "floating point parse error")?),
+            FieldType::Date     => self.insert_date(field_name,
+                temporal::parse_date(&value_str, format)?),
+            FieldType::DateTime => self.insert_datetime(field_name,
+                temporal::parse_datetime(&value_str, format)?),
+            FieldType::Duration => self.insert_duration(field_name,
+                temporal::parse_duration(&value_str)?),
         }
         Ok(())
     }
@@ -191,6 +349,46 @@ impl DataStore {
         }
     }
 
+    /// Merge sparse floating-point vector into data store under specified field name
+    pub fn merge_sparse_float(&mut self, field_name: &String, v: SparseVec) -> Result<()> {
+        self.add_field(field_name.clone(), FieldType::Float);
+        match self.sparse.insert(field_name.clone(), v) {
+            Some(_) => { Err(Error::from_kind(ErrorKind::DataFrameError(
+                format!("merging field {} clobbered existing field", field_name)))) },
+            None    => { Ok(()) }
+        }
+    }
+
+    /// Merge date vector into data store under specified field name
+    pub fn merge_date(&mut self, field_name: &String, v: Vec<NaiveDate>) -> Result<()> {
+        self.add_field(field_name.clone(), FieldType::Date);
+        match self.date.insert(field_name.clone(), v) {
+            Some(_) => { Err(Error::from_kind(ErrorKind::DataFrameError(
+                format!("merging field {} clobbered existing field", field_name)))) },
+            None    => { Ok(()) }
+        }
+    }
+
+    /// Merge date-time vector into data store under specified field name
+    pub fn merge_datetime(&mut self, field_name: &String, v: Vec<NaiveDateTime>) -> Result<()> {
+        self.add_field(field_name.clone(), FieldType::DateTime);
+        match self.datetime.insert(field_name.clone(), v) {
+            Some(_) => { Err(Error::from_kind(ErrorKind::DataFrameError(
+                format!("merging field {} clobbered existing field", field_name)))) },
+            None    => { Ok(()) }
+        }
+    }
+
+    /// Merge duration vector into data store under specified field name
+    pub fn merge_duration(&mut self, field_name: &String, v: Vec<Duration>) -> Result<()> {
+        self.add_field(field_name.clone(), FieldType::Duration);
+        match self.duration.insert(field_name.clone(), v) {
+            Some(_) => { Err(Error::from_kind(ErrorKind::DataFrameError(
+                format!("merging field {} clobbered existing field", field_name)))) },
+            None    => { Ok(()) }
+        }
+    }
+
     /// Merge the fields of a given field type with specified field names from source datastore
     /// into this data store
     pub fn merge_fields(&mut self, field_names: Vec<&String>, field_type: &FieldType,
@@ -213,15 +411,42 @@ impl DataStore {
                     try!(src.boolean.get(field_name)
                     .ok_or(format!("unable to merge field_name {}: does not exist", field_name)))
                         .clone())),
-                FieldType::Float    => try!(self.merge_float(field_name,
-                    try!(src.float.get(field_name)
-                    .ok_or(format!("unable to merge field_name {}: does not exist", field_name)))
-                        .clone())),
+                FieldType::Float    => match src.sparse.get(field_name) {
+                    Some(sparse) => self.merge_sparse_float(field_name, sparse.clone())?,
+                    None => self.merge_float(field_name, src.float.get(field_name)
+                        .ok_or(format!("unable to merge field_name {}: does not exist",
+                            field_name))?
+                        .clone())?,
+                },
+                FieldType::Date     => self.merge_date(field_name, src.date.get(field_name)
+                    .ok_or(format!("unable to merge field_name {}: does not exist", field_name))?
+                        .clone())?,
+                FieldType::DateTime => self.merge_datetime(field_name,
+                    src.datetime.get(field_name)
+                    .ok_or(format!("unable to merge field_name {}: does not exist", field_name))?
+                        .clone())?,
+                FieldType::Duration => self.merge_duration(field_name,
+                    src.duration.get(field_name)
+                    .ok_or(format!("unable to merge field_name {}: does not exist", field_name))?
+                        .clone())?,
+            }
+            if let Some(mask) = src.nulls.get(field_name) {
+                self.merge_nulls(field_name, mask.clone());
             }
         }
         Ok(())
     }
 
+    /// Set the null mask (true for each missing value) of a field in this data store. The mask is
+    /// only stored if it contains at least one missing value.
+    pub fn merge_nulls(&mut self, field_name: &str, mask: Vec<bool>) {
+        if mask.iter().any(|&null| null) {
+            self.nulls.insert(field_name.to_string(), mask);
+        } else {
+            self.nulls.remove(field_name);
+        }
+    }
+
     /// Merge single field of the given field type and specified field name from source data store
     /// into this data store
     pub fn merge_field(&mut self, field_name: &String, field_type: &FieldType, src: &DataStore)
@@ -236,6 +461,326 @@ impl DataStore {
         }
         Ok(())
     }
+    /// Append the rows of a source data store into this data store. The source data store must
+    /// have the same field names and field types as this data store (unless this data store is
+    /// empty). If `fill_missing` is true, fields missing from one of the data stores are instead
+    /// filled with missing values for the appended rows. Appending a data store without any fields
+    /// (e.g. from a source file without any rows) has no effect.
+    pub fn append(&mut self, other: DataStore, fill_missing: bool) -> Result<()> {
+        if self.fields.is_empty() {
+            return self.merge(other);
+        }
+        if other.fields.is_empty() {
+            return Ok(());
+        }
+        let (self_nrows, other_nrows) = (self.nrows(), other.nrows());
+
+        // check field compatibility before modifying anything
+        for field in &other.fields {
+            match self.get_fieldinfo(&field.name).map(|fi| fi.ty) {
+                Some(ty) if ty != field.ty => {
+                    return Err(Error::from_kind(ErrorKind::DataFrameError(
+                        format!("unable to append: field {} has type {:?}, expected {:?}",
+                            field.name, field.ty, ty))));
+                }
+                None if !fill_missing => {
+                    return Err(Error::from_kind(ErrorKind::DataFrameError(
+                        format!("unable to append: unexpected field {}", field.name))));
+                }
+                _ => {}
+            }
+        }
+        let missing_fields: Vec<FieldInfo> = self.fields.iter()
+            .filter(|fi| !other.field_map.contains_key(&fi.name)).cloned().collect();
+        if !fill_missing && !missing_fields.is_empty() {
+            return Err(Error::from_kind(ErrorKind::DataFrameError(
+                format!("unable to append: missing field {}", missing_fields[0].name))));
+        }
+
+        for field in &other.fields {
+            if !self.field_map.contains_key(&field.name) {
+                self.add_field(field.name.clone(), field.ty);
+                if let Some(sparse) = other.sparse.get(&field.name) {
+                    // keep sparse storage for sparse fields missing from this data store
+                    self.sparse.insert(field.name.clone(),
+                        SparseVec::new(0, sparse.default_value()));
+                }
+                self.extend_default(&field.name, field.ty, self_nrows);
+                self.nulls.insert(field.name.clone(), vec![true; self_nrows]);
+            }
+        }
+
+        let DataStore { fields, mut unsigned, mut signed, mut text, mut boolean, mut float,
+            mut sparse, mut date, mut datetime, mut duration, mut nulls, .. } = other;
+        for field in fields {
+            let other_mask = nulls.remove(&field.name);
+            self.append_nulls(&field.name, self_nrows, other_mask, other_nrows);
+            match field.ty {
+                FieldType::Unsigned => append_values(&mut self.unsigned, &mut unsigned, field.name),
+                FieldType::Signed   => append_values(&mut self.signed, &mut signed, field.name),
+                FieldType::Text     => append_values(&mut self.text, &mut text, field.name),
+                FieldType::Boolean  => append_values(&mut self.boolean, &mut boolean, field.name),
+                FieldType::Float    => self.append_float(&mut float, &mut sparse, field.name),
+                FieldType::Date     => append_values(&mut self.date, &mut date, field.name),
+                FieldType::DateTime => append_values(&mut self.datetime, &mut datetime,
+                    field.name),
+                FieldType::Duration => append_values(&mut self.duration, &mut duration,
+                    field.name),
+            }
+        }
+        for field in missing_fields {
+            self.extend_default(&field.name, field.ty, other_nrows);
+            self.append_nulls(&field.name, self_nrows, Some(vec![true; other_nrows]),
+                other_nrows);
+        }
+        Ok(())
+    }
+
+    /// Extend the null mask of a field by the null mask of appended rows
+    fn append_nulls(&mut self, field_name: &str, nrows: usize, other_mask: Option<Vec<bool>>,
+            other_nrows: usize) {
+        if !self.nulls.contains_key(field_name) && other_mask.is_none() {
+            return;
+        }
+        let mask = self.nulls.entry(field_name.to_string())
+            .or_insert_with(|| vec![false; nrows]);
+        match other_mask {
+            Some(other_mask) => mask.extend(other_mask),
+            None             => mask.resize(nrows + other_nrows, false),
+        }
+    }
+
+    /// Append the values of a floating-point field, keeping sparse storage if the field is stored
+    /// sparsely in both data stores
+    fn append_float(&mut self, float: &mut HashMap<String, Vec<f64>>,
+            sparse: &mut HashMap<String, SparseVec>, field_name: String) {
+        let other_sparse = sparse.remove(&field_name);
+        if let Some(self_sparse) = self.sparse.get_mut(&field_name) {
+            if let Some(other_sparse) = other_sparse {
+                self_sparse.append(other_sparse);
+                return;
+            }
+        }
+        if other_sparse.is_none() && !self.sparse.contains_key(&field_name) {
+            append_values(&mut self.float, float, field_name);
+            return;
+        }
+        // mixed storage, so store the combined field densely
+        let mut values = self.sparse.remove(&field_name).map(|v| v.to_dense())
+            .or_else(|| self.float.remove(&field_name)).unwrap_or_default();
+        values.extend(other_sparse.map(|v| v.to_dense())
+            .or_else(|| float.remove(&field_name)).unwrap_or_default());
+        self.float.insert(field_name, values);
+    }
+
+    fn extend_default(&mut self, field_name: &str, field_type: FieldType, n: usize) {
+        if let Some(sparse) = self.sparse.get_mut(field_name) {
+            sparse.extend(f64::NAN, n);
+            return;
+        }
+        match field_type {
+            FieldType::Unsigned => extend_values(&mut self.unsigned, field_name, 0, n),
+            FieldType::Signed   => extend_values(&mut self.signed, field_name, 0, n),
+            FieldType::Text     => extend_values(&mut self.text, field_name, String::new(), n),
+            FieldType::Boolean  => extend_values(&mut self.boolean, field_name, false, n),
+            FieldType::Float    => extend_values(&mut self.float, field_name, f64::NAN, n),
+            FieldType::Date     => extend_values(&mut self.date, field_name,
+                temporal::epoch_date(), n),
+            FieldType::DateTime => extend_values(&mut self.datetime, field_name,
+                temporal::epoch_datetime(), n),
+            FieldType::Duration => extend_values(&mut self.duration, field_name,
+                Duration::zero(), n),
+        }
+    }
+
+    /// Join this data store with another data store, matching rows on the values of the specified
+    /// key fields (which must exist, with the same field types, in both data stores). The
+    /// resulting data store contains a single copy of the key fields, followed by all other
+    /// fields from this data store and then all other fields from the other data store. Fields
+    /// of unmatched rows (in left and outer joins) are filled with missing values.
+    pub fn join(&self, other: &DataStore, keys: &[String], method: JoinMethod)
+            -> Result<DataStore> {
+        if keys.is_empty() {
+            return Err(Error::from_kind(ErrorKind::DataFrameError(
+                "unable to join: no key fields specified".to_string())));
+        }
+        for key in keys {
+            match (self.get_fieldinfo(key), other.get_fieldinfo(key)) {
+                (Some(left), Some(right)) if left.ty == right.ty => {}
+                (Some(left), Some(right)) => {
+                    return Err(Error::from_kind(ErrorKind::DataFrameError(
+                        format!("unable to join: key field {} has type {:?} and type {:?}", key,
+                            left.ty, right.ty))));
+                }
+                _ => {
+                    return Err(Error::from_kind(ErrorKind::DataFrameError(
+                        format!("unable to join: key field {} missing", key))));
+                }
+            }
+        }
+        for field in &other.fields {
+            if !keys.contains(&field.name) && self.field_map.contains_key(&field.name) {
+                return Err(Error::from_kind(ErrorKind::DataFrameError(
+                    format!("unable to join: non-key field {} exists in both data stores",
+                        field.name))));
+            }
+        }
+
+        // find the matching (left, right) row pairs
+        let left_keys = self.key_values(keys);
+        let right_keys = other.key_values(keys);
+        // missing key values never match
+        let mut right_index: HashMap<&[KeyValue], Vec<usize>> = HashMap::new();
+        for (j, key) in right_keys.iter().enumerate() {
+            if let Some(ref key) = *key {
+                right_index.entry(&key[..]).or_default().push(j);
+            }
+        }
+        let mut left_rows: Vec<Option<usize>> = Vec::new();
+        let mut right_rows: Vec<Option<usize>> = Vec::new();
+        let mut right_matched = vec![false; right_keys.len()];
+        for (i, key) in left_keys.iter().enumerate() {
+            match key.as_ref().and_then(|key| right_index.get(&key[..])) {
+                Some(matches) => {
+                    for &j in matches {
+                        left_rows.push(Some(i));
+                        right_rows.push(Some(j));
+                        right_matched[j] = true;
+                    }
+                }
+                None => {
+                    if method != JoinMethod::Inner {
+                        left_rows.push(Some(i));
+                        right_rows.push(None);
+                    }
+                }
+            }
+        }
+        if method == JoinMethod::Outer {
+            for (j, matched) in right_matched.iter().enumerate() {
+                if !matched {
+                    left_rows.push(None);
+                    right_rows.push(Some(j));
+                }
+            }
+        }
+
+        let mut joined = DataStore::empty();
+        for field in &self.fields {
+            if keys.contains(&field.name) {
+                joined.gather_key_field(self, other, field, &left_rows, &right_rows)?;
+            }
+        }
+        for field in &self.fields {
+            if !keys.contains(&field.name) {
+                joined.gather_field(self, field, &left_rows)?;
+            }
+        }
+        for field in &other.fields {
+            if !keys.contains(&field.name) {
+                joined.gather_field(other, field, &right_rows)?;
+            }
+        }
+        Ok(joined)
+    }
+
+    /// Generate a key for each row from the values of the specified fields. Rows with a missing
+    /// (or NaN) value in any of the fields have no key.
+    fn key_values(&self, keys: &[String]) -> Vec<Option<Vec<KeyValue<'_>>>> {
+        let mut row_keys = vec![Some(Vec::with_capacity(keys.len())); self.nrows()];
+        for key in keys {
+            let fi = self.get_fieldinfo(key).expect("datastore inconsistent");
+            match fi.ty {
+                FieldType::Unsigned => append_keys(&mut row_keys,
+                    self.unsigned[key].iter().map(|&u| Some(KeyValue::Unsigned(u)))),
+                FieldType::Signed   => append_keys(&mut row_keys,
+                    self.signed[key].iter().map(|&s| Some(KeyValue::Signed(s)))),
+                FieldType::Text     => append_keys(&mut row_keys,
+                    self.text[key].iter().map(|s| Some(KeyValue::Text(s)))),
+                FieldType::Boolean  => append_keys(&mut row_keys,
+                    self.boolean[key].iter().map(|&b| Some(KeyValue::Boolean(b)))),
+                FieldType::Float    => append_keys(&mut row_keys,
+                    self.get_float_values(key).expect("datastore inconsistent").iter()
+                        .map(|&f| float_key(f))),
+                FieldType::Date     => append_keys(&mut row_keys,
+                    self.date[key].iter().map(|&d| Some(KeyValue::Date(d)))),
+                FieldType::DateTime => append_keys(&mut row_keys,
+                    self.datetime[key].iter().map(|&dt| Some(KeyValue::DateTime(dt)))),
+                FieldType::Duration => append_keys(&mut row_keys,
+                    self.duration[key].iter().map(|&d| Some(KeyValue::Duration(d)))),
+            }
+            if let Some(mask) = self.nulls.get(key) {
+                for (row_key, &null) in row_keys.iter_mut().zip(mask) {
+                    if null {
+                        *row_key = None;
+                    }
+                }
+            }
+        }
+        row_keys
+    }
+
+    /// Merge the specified rows of a field in a source data store into this data store, filling
+    /// missing rows with missing values
+    fn gather_field(&mut self, src: &DataStore, field: &FieldInfo, rows: &[Option<usize>])
+            -> Result<()> {
+        let name = &field.name;
+        self.merge_nulls(name, gather_nulls(src.nulls.get(name), rows));
+        match field.ty {
+            FieldType::Unsigned => self.merge_unsigned(name,
+                gather_values(&src.unsigned[name], rows, 0)),
+            FieldType::Signed   => self.merge_signed(name,
+                gather_values(&src.signed[name], rows, 0)),
+            FieldType::Text     => self.merge_text(name,
+                gather_values(&src.text[name], rows, String::new())),
+            FieldType::Boolean  => self.merge_boolean(name,
+                gather_values(&src.boolean[name], rows, false)),
+            FieldType::Float    => match src.sparse.get(name) {
+                Some(sparse) => self.merge_sparse_float(name, sparse.gather(rows, f64::NAN)),
+                None => self.merge_float(name, gather_values(&src.float[name], rows, f64::NAN)),
+            },
+            FieldType::Date     => self.merge_date(name,
+                gather_values(&src.date[name], rows, temporal::epoch_date())),
+            FieldType::DateTime => self.merge_datetime(name,
+                gather_values(&src.datetime[name], rows, temporal::epoch_datetime())),
+            FieldType::Duration => self.merge_duration(name,
+                gather_values(&src.duration[name], rows, Duration::zero())),
+        }
+    }
+
+    /// Merge a key field into this data store, taking values from the left data store rows (or
+    /// the right data store rows, for rows which only exist in the right data store)
+    fn gather_key_field(&mut self, left: &DataStore, right: &DataStore, field: &FieldInfo,
+            left_rows: &[Option<usize>], right_rows: &[Option<usize>]) -> Result<()> {
+        let name = &field.name;
+        let left_nulls = left.nulls.get(name).cloned()
+            .unwrap_or_else(|| vec![false; left.nrows()]);
+        let right_nulls = right.nulls.get(name).cloned()
+            .unwrap_or_else(|| vec![false; right.nrows()]);
+        self.merge_nulls(name, gather_key_values(&left_nulls, &right_nulls, left_rows,
+            right_rows));
+        match field.ty {
+            FieldType::Unsigned => self.merge_unsigned(name, gather_key_values(
+                &left.unsigned[name], &right.unsigned[name], left_rows, right_rows)),
+            FieldType::Signed   => self.merge_signed(name, gather_key_values(
+                &left.signed[name], &right.signed[name], left_rows, right_rows)),
+            FieldType::Text     => self.merge_text(name, gather_key_values(
+                &left.text[name], &right.text[name], left_rows, right_rows)),
+            FieldType::Boolean  => self.merge_boolean(name, gather_key_values(
+                &left.boolean[name], &right.boolean[name], left_rows, right_rows)),
+            FieldType::Float    => self.merge_float(name, gather_key_values(
+                &left.get_float_values(name).expect("datastore inconsistent"),
+                &right.get_float_values(name).expect("datastore inconsistent"),
+                left_rows, right_rows)),
+            FieldType::Date     => self.merge_date(name, gather_key_values(
+                &left.date[name], &right.date[name], left_rows, right_rows)),
+            FieldType::DateTime => self.merge_datetime(name, gather_key_values(
+                &left.datetime[name], &right.datetime[name], left_rows, right_rows)),
+            FieldType::Duration => self.merge_duration(name, gather_key_values(
+                &left.duration[name], &right.duration[name], left_rows, right_rows)),
+        }
+    }
+
     /// Retrieve an unsigned integer field
     pub fn get_unsigned_field(&self, field_name: &String) -> Option<&Vec<u64>> {
         self.unsigned.get(field_name)
@@ -252,9 +797,45 @@ impl DataStore {
     pub fn get_boolean_field(&self, field_name: &String) -> Option<&Vec<bool>> {
         self.boolean.get(field_name)
     }
-    /// Retrieve a floating-point field
+    /// Retrieve a floating-point field. Sparsely-stored fields are converted to (and kept in)
+    /// dense form on first retrieval; use `get_float_values` to avoid keeping a dense copy.
     pub fn get_float_field(&self, field_name: &String) -> Option<&Vec<f64>> {
         self.float.get(field_name)
+            .or_else(|| self.sparse.get(field_name).map(|sparse| sparse.dense()))
+    }
+    /// Retrieve the values of a floating-point field, converting sparsely-stored fields to a
+    /// (temporary) dense vector
+    pub fn get_float_values(&self, field_name: &String) -> Option<Cow<'_, [f64]>> {
+        match self.float.get(field_name) {
+            Some(values) => Some(Cow::Borrowed(values)),
+            None => self.sparse.get(field_name).map(|sparse| Cow::Owned(sparse.to_dense())),
+        }
+    }
+    /// Retrieve a sparsely-stored floating-point field
+    pub fn get_sparse_float_field(&self, field_name: &String) -> Option<&SparseVec> {
+        self.sparse.get(field_name)
+    }
+    /// Retrieve a date field
+    pub fn get_date_field(&self, field_name: &String) -> Option<&Vec<NaiveDate>> {
+        self.date.get(field_name)
+    }
+    /// Retrieve a date-time field
+    pub fn get_datetime_field(&self, field_name: &String) -> Option<&Vec<NaiveDateTime>> {
+        self.datetime.get(field_name)
+    }
+    /// Retrieve a duration field
+    pub fn get_duration_field(&self, field_name: &String) -> Option<&Vec<Duration>> {
+        self.duration.get(field_name)
+    }
+
+    /// Retrieve the null mask (true for each missing value) of a field, if the field contains any
+    /// missing values
+    pub fn get_null_mask(&self, field_name: &str) -> Option<&Vec<bool>> {
+        self.nulls.get(field_name)
+    }
+    /// Check whether or not the value of a field in the specified row is missing
+    pub fn is_null(&self, field_name: &str, row: usize) -> bool {
+        self.nulls.get(field_name).is_some_and(|mask| mask[row])
     }
 
     /// Get the field information struct for a given field name
@@ -279,11 +860,18 @@ impl DataStore {
             .and_then(|x| is_hm_homogeneous_with(&self.text, x))
             .and_then(|x| is_hm_homogeneous_with(&self.boolean, x))
             .and_then(|x| is_hm_homogeneous_with(&self.float, x))
+            .and_then(|x| is_sparse_homogeneous_with(&self.sparse, x))
+            .and_then(|x| is_hm_homogeneous_with(&self.date, x))
+            .and_then(|x| is_hm_homogeneous_with(&self.datetime, x))
+            .and_then(|x| is_hm_homogeneous_with(&self.duration, x))
             .is_some()
     }
     /// Retrieve number of rows for this data store
     pub fn nrows(&self) -> usize {
         [max_len(&self.unsigned), max_len(&self.signed), max_len(&self.text),
-            max_len(&self.boolean), max_len(&self.float)].iter().fold(0, |acc, l| max(acc, *l))
+            max_len(&self.boolean), max_len(&self.float), max_len(&self.date),
+            max_len(&self.datetime), max_len(&self.duration),
+            self.sparse.values().map(|v| v.len()).max().unwrap_or(0)].iter()
+            .fold(0, |acc, l| max(acc, *l))
     }
 }
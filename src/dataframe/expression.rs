@@ -0,0 +1,840 @@
+//! Expression mini-language for computing a field from other fields
+//!
+//! Expressions combine field names, literals (numbers, "strings" and `true` / `false`),
+//! arithmetic (`+ - * / %`), comparison (`== != < <= > >=`) and logical (`&& || !`) operators, and
+//! function calls, e.g. `income / household_income`, `log(age + 1)` or
+//! `if(gender_code == "M", 0, 1)`. Field names which aren't plain identifiers can be quoted with
+//! backticks. Expressions are type-checked against the types of the fields they refer to, and
+//! evaluated column-wise.
+//!
+//! Functions:
+//! * `log`, `log10`, `exp`, `sqrt`, `floor`, `ceil`, `round`, `pow(x, y)`: floating-point math
+//! * `abs`, `min(x, y)`, `max(x, y)`: integer results for integer arguments
+//! * `if(condition, then, otherwise)`, `is_null(x)`, `coalesce(x, fallback)`
+//! * `concat(x, ...)` (of values of any type), `len`, `upper`, `lower`, `trim`
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::f64;
+use std::fmt;
+
+use errors::*;
+
+use dataframe::{DataStore, FieldType};
+
+/// Parsed expression
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expression {
+    root: Expr,
+}
+
+impl Expression {
+    /// Parse an expression
+    pub fn parse(s: &str) -> Result<Expression> {
+        let tokens = tokenize(s)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let root = parser.parse_or()?;
+        if let Some(&(ref token, offset)) = parser.tokens.get(parser.pos) {
+            return Err(expression_error(format!("unexpected {} at position {}", token, offset)));
+        }
+        Ok(Expression { root })
+    }
+
+    /// Names of the fields this expression refers to (in order of first reference)
+    pub fn fields(&self) -> Vec<&str> {
+        let mut fields = vec![];
+        self.root.collect_fields(&mut fields);
+        fields
+    }
+
+    /// Evaluate this expression over the fields of a data store, generating a field with the
+    /// specified name. Results are missing wherever an operand is missing (except for the `is_null`
+    /// and `coalesce` functions, and the branch of `if` that isn't taken), or where integer
+    /// arithmetic overflows.
+    pub fn evaluate(&self, ds: &DataStore, target_name: &String) -> Result<DataStore> {
+        let mut field_types = HashMap::new();
+        for field_name in self.fields() {
+            let finfo = ds.get_fieldinfo(&field_name.to_string()).ok_or_else(|| expression_error(
+                format!("field {} does not exist", field_name)))?;
+            field_types.insert(field_name, finfo.ty);
+        }
+        self.root.check(&field_types)?;
+
+        let column = self.root.evaluate(ds, ds.nrows())?;
+        let mut tf_data = DataStore::empty();
+        match column.values {
+            Values::Integer(values) => tf_data.merge_signed(target_name, values)?,
+            Values::Float(values)   => tf_data.merge_float(target_name, values)?,
+            Values::Text(values)    => tf_data.merge_text(target_name, values)?,
+            Values::Boolean(values) => tf_data.merge_boolean(target_name, values)?,
+        }
+        tf_data.merge_nulls(target_name, column.nulls);
+        Ok(tf_data)
+    }
+}
+
+fn expression_error(message: String) -> Error {
+    Error::from_kind(ErrorKind::DataConfigError(format!("expression: {}", message)))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Integer(i64),
+    Float(f64),
+    Text(String),
+    Ident(String),
+    Field(String),
+    LParen,
+    RParen,
+    Comma,
+    Op(&'static str),
+}
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Token::Integer(i)      => write!(f, "number {}", i),
+            Token::Float(x)        => write!(f, "number {}", x),
+            Token::Text(ref s)     => write!(f, "string \"{}\"", s),
+            Token::Ident(ref name) => write!(f, "'{}'", name),
+            Token::Field(ref name) => write!(f, "`{}`", name),
+            Token::LParen          => write!(f, "'('"),
+            Token::RParen          => write!(f, "')'"),
+            Token::Comma           => write!(f, "','"),
+            Token::Op(op)          => write!(f, "'{}'", op),
+        }
+    }
+}
+
+const OPERATORS: [&str; 16] = ["==", "!=", "<=", ">=", "&&", "||", "+", "-", "*", "/", "%", "<",
+    ">", "!", "(", ")"];
+
+/// Split an expression into tokens (with their character offsets)
+fn tokenize(s: &str) -> Result<Vec<(Token, usize)>> {
+    let chars: Vec<char> = s.chars().collect();
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        if c.is_ascii_digit() || (c == '.' && chars.get(i + 1).is_some_and(|c| c.is_ascii_digit()))
+        {
+            let mut is_float = false;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                is_float |= chars[i] == '.';
+                i += 1;
+            }
+            if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') {
+                is_float = true;
+                i += 1;
+                if i < chars.len() && (chars[i] == '+' || chars[i] == '-') {
+                    i += 1;
+                }
+                while i < chars.len() && chars[i].is_ascii_digit() {
+                    i += 1;
+                }
+            }
+            let number: String = chars[start..i].iter().collect();
+            let token = match number.parse::<i64>() {
+                Ok(value) if !is_float => Token::Integer(value),
+                _ => Token::Float(number.parse().map_err(|_| expression_error(
+                    format!("invalid number {} at position {}", number, start)))?),
+            };
+            tokens.push((token, start));
+        } else if c.is_alphabetic() || c == '_' {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push((Token::Ident(chars[start..i].iter().collect()), start));
+        } else if c == '"' || c == '\'' || c == '`' {
+            // quoted string (or field name, for backticks), with backslash escapes
+            let mut value = String::new();
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    Some(&ch) if ch == c => { break; }
+                    Some(&'\\') if i + 1 < chars.len() => {
+                        value.push(chars[i + 1]);
+                        i += 2;
+                    }
+                    Some(&ch) => {
+                        value.push(ch);
+                        i += 1;
+                    }
+                    None => {
+                        return Err(expression_error(format!(
+                            "unterminated quote at position {}", start)));
+                    }
+                }
+            }
+            i += 1;
+            tokens.push((if c == '`' { Token::Field(value) } else { Token::Text(value) }, start));
+        } else if c == ',' {
+            i += 1;
+            tokens.push((Token::Comma, start));
+        } else {
+            let op = OPERATORS.iter().find(|op| {
+                op.chars().enumerate().all(|(j, op_c)| chars.get(i + j) == Some(&op_c))
+            }).ok_or_else(|| expression_error(format!(
+                "unexpected character '{}' at position {}", c, start)))?;
+            i += op.len();
+            tokens.push((match *op {
+                "(" => Token::LParen,
+                ")" => Token::RParen,
+                op  => Token::Op(op),
+            }, start));
+        }
+    }
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum UnaryOp { Neg, Not }
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BinaryOp { Add, Sub, Mul, Div, Rem, Eq, Ne, Lt, Le, Gt, Ge, And, Or }
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Function {
+    Log, Log10, Exp, Sqrt, Abs, Floor, Ceil, Round, Pow, Min, Max, If, IsNull, Coalesce, Concat,
+    Len, Upper, Lower, Trim,
+}
+impl Function {
+    fn from_name(name: &str) -> Option<Function> {
+        Some(match name {
+            "log"      => Function::Log,
+            "log10"    => Function::Log10,
+            "exp"      => Function::Exp,
+            "sqrt"     => Function::Sqrt,
+            "abs"      => Function::Abs,
+            "floor"    => Function::Floor,
+            "ceil"     => Function::Ceil,
+            "round"    => Function::Round,
+            "pow"      => Function::Pow,
+            "min"      => Function::Min,
+            "max"      => Function::Max,
+            "if"       => Function::If,
+            "is_null"  => Function::IsNull,
+            "coalesce" => Function::Coalesce,
+            "concat"   => Function::Concat,
+            "len"      => Function::Len,
+            "upper"    => Function::Upper,
+            "lower"    => Function::Lower,
+            "trim"     => Function::Trim,
+            _ => { return None; }
+        })
+    }
+    /// Whether or not this function accepts the specified number of arguments
+    fn accepts(&self, nargs: usize) -> bool {
+        match *self {
+            Function::Pow | Function::Min | Function::Max | Function::Coalesce => nargs == 2,
+            Function::If => nargs == 3,
+            Function::Concat => nargs >= 1,
+            _ => nargs == 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Literal {
+    Integer(i64),
+    Float(f64),
+    Text(String),
+    Boolean(bool),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Literal(Literal),
+    Field(String),
+    Unary(UnaryOp, Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    Call(Function, Vec<Expr>),
+}
+
+/// Recursive-descent expression parser
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+}
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(token, _)| token)
+    }
+    fn next(&mut self) -> Result<(Token, usize)> {
+        let token = self.tokens.get(self.pos).cloned()
+            .ok_or_else(|| expression_error("unexpected end of expression".to_string()))?;
+        self.pos += 1;
+        Ok(token)
+    }
+    fn expect(&mut self, expected: Token) -> Result<()> {
+        let (token, offset) = self.next()?;
+        if token != expected {
+            return Err(expression_error(format!("expected {} at position {}, found {}",
+                expected, offset, token)));
+        }
+        Ok(())
+    }
+    /// Parse a left-associative sequence of binary operations of the same precedence
+    fn parse_binary<F>(&mut self, ops: &[(&str, BinaryOp)], parse_operand: F) -> Result<Expr>
+            where F: Fn(&mut Parser) -> Result<Expr> {
+        let mut lhs = parse_operand(self)?;
+        while let Some(&Token::Op(op)) = self.peek() {
+            let op = match ops.iter().find(|&&(symbol, _)| symbol == op) {
+                Some(&(_, op)) => op,
+                None => { break; }
+            };
+            self.pos += 1;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(parse_operand(self)?));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_or(&mut self) -> Result<Expr> {
+        self.parse_binary(&[("||", BinaryOp::Or)], Parser::parse_and)
+    }
+    fn parse_and(&mut self) -> Result<Expr> {
+        self.parse_binary(&[("&&", BinaryOp::And)], Parser::parse_comparison)
+    }
+    fn parse_comparison(&mut self) -> Result<Expr> {
+        // comparisons don't chain (e.g. `a < b < c` is an error)
+        let lhs = self.parse_additive()?;
+        let op = match self.peek() {
+            Some(&Token::Op("==")) => BinaryOp::Eq,
+            Some(&Token::Op("!=")) => BinaryOp::Ne,
+            Some(&Token::Op("<"))  => BinaryOp::Lt,
+            Some(&Token::Op("<=")) => BinaryOp::Le,
+            Some(&Token::Op(">"))  => BinaryOp::Gt,
+            Some(&Token::Op(">=")) => BinaryOp::Ge,
+            _ => { return Ok(lhs); }
+        };
+        self.pos += 1;
+        Ok(Expr::Binary(op, Box::new(lhs), Box::new(self.parse_additive()?)))
+    }
+    fn parse_additive(&mut self) -> Result<Expr> {
+        self.parse_binary(&[("+", BinaryOp::Add), ("-", BinaryOp::Sub)],
+            Parser::parse_multiplicative)
+    }
+    fn parse_multiplicative(&mut self) -> Result<Expr> {
+        self.parse_binary(&[("*", BinaryOp::Mul), ("/", BinaryOp::Div), ("%", BinaryOp::Rem)],
+            Parser::parse_unary)
+    }
+    fn parse_unary(&mut self) -> Result<Expr> {
+        match self.peek() {
+            Some(&Token::Op("-")) => {
+                self.pos += 1;
+                Ok(Expr::Unary(UnaryOp::Neg, Box::new(self.parse_unary()?)))
+            }
+            Some(&Token::Op("!")) => {
+                self.pos += 1;
+                Ok(Expr::Unary(UnaryOp::Not, Box::new(self.parse_unary()?)))
+            }
+            _ => self.parse_primary(),
+        }
+    }
+    fn parse_primary(&mut self) -> Result<Expr> {
+        let (token, offset) = self.next()?;
+        match token {
+            Token::Integer(i) => Ok(Expr::Literal(Literal::Integer(i))),
+            Token::Float(x)   => Ok(Expr::Literal(Literal::Float(x))),
+            Token::Text(s)    => Ok(Expr::Literal(Literal::Text(s))),
+            Token::Field(name) => Ok(Expr::Field(name)),
+            Token::LParen     => {
+                let expr = self.parse_or()?;
+                self.expect(Token::RParen)?;
+                Ok(expr)
+            }
+            Token::Ident(name) => {
+                if self.peek() == Some(&Token::LParen) {
+                    return self.parse_call(&name, offset);
+                }
+                Ok(match &name[..] {
+                    "true"  => Expr::Literal(Literal::Boolean(true)),
+                    "false" => Expr::Literal(Literal::Boolean(false)),
+                    _       => Expr::Field(name),
+                })
+            }
+            token => Err(expression_error(format!("unexpected {} at position {}", token,
+                offset))),
+        }
+    }
+    fn parse_call(&mut self, name: &str, offset: usize) -> Result<Expr> {
+        let function = Function::from_name(name).ok_or_else(|| expression_error(format!(
+            "unknown function {} at position {}", name, offset)))?;
+        self.expect(Token::LParen)?;
+        let mut args = vec![];
+        if self.peek() == Some(&Token::RParen) {
+            self.pos += 1;
+        } else {
+            loop {
+                args.push(self.parse_or()?);
+                match self.next()? {
+                    (Token::Comma, _)  => {}
+                    (Token::RParen, _) => { break; }
+                    (token, offset) => {
+                        return Err(expression_error(format!(
+                            "expected ',' or ')' at position {}, found {}", offset, token)));
+                    }
+                }
+            }
+        }
+        if !function.accepts(args.len()) {
+            return Err(expression_error(format!("wrong number of arguments to {} at position {}",
+                name, offset)));
+        }
+        Ok(Expr::Call(function, args))
+    }
+}
+
+/// Type of an (intermediate) expression value
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ExprType { Integer, Float, Text, Boolean }
+impl ExprType {
+    fn is_numeric(self) -> bool {
+        self == ExprType::Integer || self == ExprType::Float
+    }
+    /// Common type of two values (e.g. the branches of an `if`), if any
+    fn unify(self, other: ExprType) -> Option<ExprType> {
+        if self == other {
+            Some(self)
+        } else if self.is_numeric() && other.is_numeric() {
+            Some(ExprType::Float)
+        } else {
+            None
+        }
+    }
+}
+
+fn type_error<T>(message: &str, types: &[ExprType]) -> Result<T> {
+    Err(expression_error(format!("{} (found {:?})", message, types)))
+}
+
+impl Expr {
+    fn collect_fields<'a>(&'a self, fields: &mut Vec<&'a str>) {
+        match *self {
+            Expr::Literal(_) => {}
+            Expr::Field(ref name) => {
+                if !fields.contains(&&name[..]) {
+                    fields.push(name);
+                }
+            }
+            Expr::Unary(_, ref operand) => operand.collect_fields(fields),
+            Expr::Binary(_, ref lhs, ref rhs) => {
+                lhs.collect_fields(fields);
+                rhs.collect_fields(fields);
+            }
+            Expr::Call(_, ref args) => {
+                for arg in args {
+                    arg.collect_fields(fields);
+                }
+            }
+        }
+    }
+
+    /// Type-check this expression, returning its type
+    fn check(&self, field_types: &HashMap<&str, FieldType>) -> Result<ExprType> {
+        match *self {
+            Expr::Literal(ref literal) => Ok(match *literal {
+                Literal::Integer(_) => ExprType::Integer,
+                Literal::Float(_)   => ExprType::Float,
+                Literal::Text(_)    => ExprType::Text,
+                Literal::Boolean(_) => ExprType::Boolean,
+            }),
+            Expr::Field(ref name) => match field_types.get(&name[..]) {
+                Some(&FieldType::Unsigned) | Some(&FieldType::Signed) => Ok(ExprType::Integer),
+                Some(&FieldType::Float)   => Ok(ExprType::Float),
+                Some(&FieldType::Text)    => Ok(ExprType::Text),
+                Some(&FieldType::Boolean) => Ok(ExprType::Boolean),
+                Some(ty) => Err(expression_error(format!(
+                    "field {} has unsupported type {:?}", name, ty))),
+                None => Err(expression_error(format!("field {} does not exist", name))),
+            },
+            Expr::Unary(op, ref operand) => {
+                let ty = operand.check(field_types)?;
+                match op {
+                    UnaryOp::Neg if ty.is_numeric() => Ok(ty),
+                    UnaryOp::Not if ty == ExprType::Boolean => Ok(ty),
+                    UnaryOp::Neg => type_error("negation requires a number", &[ty]),
+                    UnaryOp::Not => type_error("'!' requires a boolean", &[ty]),
+                }
+            }
+            Expr::Binary(op, ref lhs, ref rhs) => {
+                let (lty, rty) = (lhs.check(field_types)?, rhs.check(field_types)?);
+                let numeric = lty.is_numeric() && rty.is_numeric();
+                match op {
+                    BinaryOp::Add if lty == ExprType::Text && rty == ExprType::Text => {
+                        Ok(ExprType::Text)
+                    }
+                    BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Rem if numeric => {
+                        Ok(lty.unify(rty).unwrap())
+                    }
+                    BinaryOp::Div if numeric => Ok(ExprType::Float),
+                    BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div
+                            | BinaryOp::Rem => {
+                        type_error("arithmetic requires numbers (or strings for '+')",
+                            &[lty, rty])
+                    }
+                    BinaryOp::Eq | BinaryOp::Ne if lty.unify(rty).is_some() => {
+                        Ok(ExprType::Boolean)
+                    }
+                    BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge
+                            if numeric || (lty == ExprType::Text && rty == ExprType::Text) => {
+                        Ok(ExprType::Boolean)
+                    }
+                    BinaryOp::Eq | BinaryOp::Ne | BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt
+                            | BinaryOp::Ge => {
+                        type_error("comparison requires values of the same type", &[lty, rty])
+                    }
+                    BinaryOp::And | BinaryOp::Or
+                            if lty == ExprType::Boolean && rty == ExprType::Boolean => {
+                        Ok(ExprType::Boolean)
+                    }
+                    BinaryOp::And | BinaryOp::Or => {
+                        type_error("logical operators require booleans", &[lty, rty])
+                    }
+                }
+            }
+            Expr::Call(function, ref args) => {
+                let types = args.iter().map(|arg| arg.check(field_types))
+                    .collect::<Result<Vec<_>>>()?;
+                check_call(function, &types)
+            }
+        }
+    }
+
+    /// Evaluate this (type-checked) expression over `nrows` rows of a data store
+    fn evaluate(&self, ds: &DataStore, nrows: usize) -> Result<Column> {
+        match *self {
+            Expr::Literal(ref literal) => Ok(Column::new(match *literal {
+                Literal::Integer(i)     => Values::Integer(vec![i; nrows]),
+                Literal::Float(x)       => Values::Float(vec![x; nrows]),
+                Literal::Text(ref s)    => Values::Text(vec![s.clone(); nrows]),
+                Literal::Boolean(b)     => Values::Boolean(vec![b; nrows]),
+            })),
+            Expr::Field(ref name) => {
+                let field_name = name.to_string();
+                let mut nulls = ds.get_null_mask(name).cloned()
+                    .unwrap_or_else(|| vec![false; nrows]);
+                let values = match ds.get_fieldinfo(&field_name).map(|finfo| finfo.ty) {
+                    // values too large for a signed integer are missing, as for overflow
+                    Some(FieldType::Unsigned) => Values::Integer(ds.get_unsigned_field(&field_name)
+                        .unwrap().iter().zip(nulls.iter_mut())
+                        .map(|(&u, null)| checked(i64::try_from(u).ok(), null)).collect()),
+                    Some(FieldType::Signed)   => Values::Integer(
+                        ds.get_signed_field(&field_name).unwrap().clone()),
+                    Some(FieldType::Float)    => Values::Float(
+                        ds.get_float_values(&field_name).unwrap().into_owned()),
+                    Some(FieldType::Text)     => Values::Text(
+                        ds.get_text_field(&field_name).unwrap().clone()),
+                    Some(FieldType::Boolean)  => Values::Boolean(
+                        ds.get_boolean_field(&field_name).unwrap().clone()),
+                    _ => {
+                        return Err(expression_error(format!("field {} cannot be evaluated",
+                            name)));
+                    }
+                };
+                Ok(Column { values, nulls })
+            }
+            Expr::Unary(op, ref operand) => {
+                let mut column = operand.evaluate(ds, nrows)?;
+                column.values = match (op, column.values) {
+                    (UnaryOp::Neg, Values::Integer(values)) => {
+                        Values::Integer(values.iter().zip(column.nulls.iter_mut())
+                            .map(|(&i, null)| checked(i.checked_neg(), null)).collect())
+                    }
+                    (UnaryOp::Neg, Values::Float(values)) => {
+                        Values::Float(values.iter().map(|&x| -x).collect())
+                    }
+                    (UnaryOp::Not, Values::Boolean(values)) => {
+                        Values::Boolean(values.iter().map(|&b| !b).collect())
+                    }
+                    _ => { return Err(unchecked()); }
+                };
+                Ok(column)
+            }
+            Expr::Binary(op, ref lhs, ref rhs) => {
+                evaluate_binary(op, lhs.evaluate(ds, nrows)?, rhs.evaluate(ds, nrows)?)
+            }
+            Expr::Call(function, ref args) => {
+                let args = args.iter().map(|arg| arg.evaluate(ds, nrows))
+                    .collect::<Result<Vec<_>>>()?;
+                evaluate_call(function, args)
+            }
+        }
+    }
+}
+
+fn check_call(function: Function, types: &[ExprType]) -> Result<ExprType> {
+    match function {
+        Function::Log | Function::Log10 | Function::Exp | Function::Sqrt | Function::Floor
+                | Function::Ceil | Function::Round => {
+            if types[0].is_numeric() {
+                Ok(ExprType::Float)
+            } else {
+                type_error("math functions require a number", types)
+            }
+        }
+        Function::Abs if types[0].is_numeric() => Ok(types[0]),
+        Function::Pow if types[0].is_numeric() && types[1].is_numeric() => Ok(ExprType::Float),
+        Function::Min | Function::Max if types[0].is_numeric() && types[1].is_numeric() => {
+            Ok(types[0].unify(types[1]).unwrap())
+        }
+        Function::Abs | Function::Pow | Function::Min | Function::Max => {
+            type_error("math functions require numbers", types)
+        }
+        Function::If => {
+            if types[0] != ExprType::Boolean {
+                return type_error("if requires a boolean condition", types);
+            }
+            types[1].unify(types[2]).map_or_else(
+                || type_error("if requires branches of the same type", types), Ok)
+        }
+        Function::Coalesce => {
+            types[0].unify(types[1]).map_or_else(
+                || type_error("coalesce requires values of the same type", types), Ok)
+        }
+        Function::IsNull => Ok(ExprType::Boolean),
+        Function::Concat => Ok(ExprType::Text),
+        Function::Len if types[0] == ExprType::Text => Ok(ExprType::Integer),
+        Function::Upper | Function::Lower | Function::Trim if types[0] == ExprType::Text => {
+            Ok(ExprType::Text)
+        }
+        Function::Len | Function::Upper | Function::Lower | Function::Trim => {
+            type_error("string functions require a string", types)
+        }
+    }
+}
+
+/// Column of intermediate expression values
+#[derive(Debug, Clone, PartialEq)]
+enum Values {
+    Integer(Vec<i64>),
+    Float(Vec<f64>),
+    Text(Vec<String>),
+    Boolean(Vec<bool>),
+}
+impl Values {
+    fn len(&self) -> usize {
+        match *self {
+            Values::Integer(ref values) => values.len(),
+            Values::Float(ref values)   => values.len(),
+            Values::Text(ref values)    => values.len(),
+            Values::Boolean(ref values) => values.len(),
+        }
+    }
+    /// Convert numeric values into floating-point values
+    fn into_float(self) -> Result<Vec<f64>> {
+        match self {
+            Values::Integer(values) => Ok(values.iter().map(|&i| i as f64).collect()),
+            Values::Float(values)   => Ok(values),
+            _ => Err(unchecked()),
+        }
+    }
+    /// Convert values into strings (for concatenation)
+    fn into_text(self) -> Vec<String> {
+        match self {
+            Values::Integer(values) => values.iter().map(|i| i.to_string()).collect(),
+            Values::Float(values)   => values.iter().map(|x| x.to_string()).collect(),
+            Values::Text(values)    => values,
+            Values::Boolean(values) => values.iter().map(|b| b.to_string()).collect(),
+        }
+    }
+    /// Select the values of `self` where `mask` is true and the values of `other` elsewhere
+    fn select(self, mask: &[bool], other: Values) -> Result<Values> {
+        fn select<T>(mask: &[bool], a: Vec<T>, b: Vec<T>) -> Vec<T> {
+            mask.iter().zip(a.into_iter().zip(b)).map(|(&m, (a, b))| if m { a } else { b })
+                .collect()
+        }
+        Ok(match (self, other) {
+            (Values::Integer(a), Values::Integer(b)) => Values::Integer(select(mask, a, b)),
+            (Values::Text(a), Values::Text(b))       => Values::Text(select(mask, a, b)),
+            (Values::Boolean(a), Values::Boolean(b)) => Values::Boolean(select(mask, a, b)),
+            (a, b) => Values::Float(select(mask, a.into_float()?, b.into_float()?)),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Column {
+    values: Values,
+    /// Null mask (true for each missing value)
+    nulls: Vec<bool>,
+}
+impl Column {
+    fn new(values: Values) -> Column {
+        let nulls = vec![false; values.len()];
+        Column { values, nulls }
+    }
+}
+
+/// Error for values which don't match the types found when type-checking
+fn unchecked() -> Error {
+    expression_error("value does not match checked type".to_string())
+}
+
+/// Result of a checked integer operation, which is missing on overflow
+fn checked(result: Option<i64>, null: &mut bool) -> i64 {
+    result.unwrap_or_else(|| {
+        *null = true;
+        0
+    })
+}
+
+fn evaluate_binary(op: BinaryOp, lhs: Column, rhs: Column) -> Result<Column> {
+    let mut nulls: Vec<bool> = lhs.nulls.iter().zip(&rhs.nulls).map(|(&l, &r)| l || r).collect();
+    let values = match (op, lhs.values, rhs.values) {
+        (BinaryOp::Add, Values::Text(l), Values::Text(r)) => {
+            Values::Text(l.into_iter().zip(r).map(|(l, r)| l + &r).collect())
+        }
+        (BinaryOp::Add, Values::Integer(l), Values::Integer(r))
+                | (BinaryOp::Sub, Values::Integer(l), Values::Integer(r))
+                | (BinaryOp::Mul, Values::Integer(l), Values::Integer(r))
+                | (BinaryOp::Rem, Values::Integer(l), Values::Integer(r)) => {
+            Values::Integer(l.iter().zip(&r).zip(nulls.iter_mut()).map(|((&l, &r), null)| {
+                checked(match op {
+                    BinaryOp::Add => l.checked_add(r),
+                    BinaryOp::Sub => l.checked_sub(r),
+                    BinaryOp::Mul => l.checked_mul(r),
+                    _             => l.checked_rem(r),
+                }, null)
+            }).collect())
+        }
+        (BinaryOp::Add, l, r) | (BinaryOp::Sub, l, r) | (BinaryOp::Mul, l, r)
+                | (BinaryOp::Div, l, r) | (BinaryOp::Rem, l, r) => {
+            Values::Float(l.into_float()?.iter().zip(&r.into_float()?).map(|(&l, &r)| match op {
+                BinaryOp::Add => l + r,
+                BinaryOp::Sub => l - r,
+                BinaryOp::Mul => l * r,
+                BinaryOp::Div => l / r,
+                _             => l % r,
+            }).collect())
+        }
+        (BinaryOp::And, Values::Boolean(l), Values::Boolean(r)) => {
+            Values::Boolean(l.iter().zip(&r).map(|(&l, &r)| l && r).collect())
+        }
+        (BinaryOp::Or, Values::Boolean(l), Values::Boolean(r)) => {
+            Values::Boolean(l.iter().zip(&r).map(|(&l, &r)| l || r).collect())
+        }
+        (BinaryOp::And, _, _) | (BinaryOp::Or, _, _) => { return Err(unchecked()); }
+        (op, Values::Integer(l), Values::Integer(r)) => Values::Boolean(compare(op, &l, &r)),
+        (op, Values::Text(l), Values::Text(r))       => Values::Boolean(compare(op, &l, &r)),
+        (op, Values::Boolean(l), Values::Boolean(r)) => Values::Boolean(compare(op, &l, &r)),
+        (op, l, r) => Values::Boolean(compare(op, &l.into_float()?, &r.into_float()?)),
+    };
+    Ok(Column { values, nulls })
+}
+
+fn compare<T: PartialOrd>(op: BinaryOp, lhs: &[T], rhs: &[T]) -> Vec<bool> {
+    lhs.iter().zip(rhs).map(|(l, r)| match op {
+        BinaryOp::Eq => l == r,
+        BinaryOp::Ne => l != r,
+        BinaryOp::Lt => l < r,
+        BinaryOp::Le => l <= r,
+        BinaryOp::Gt => l > r,
+        _            => l >= r,
+    }).collect()
+}
+
+fn evaluate_call(function: Function, mut args: Vec<Column>) -> Result<Column> {
+    let any_null = |args: &[Column]| -> Vec<bool> {
+        (0..args[0].nulls.len()).map(|i| args.iter().any(|arg| arg.nulls[i])).collect()
+    };
+    match function {
+        Function::Log | Function::Log10 | Function::Exp | Function::Sqrt | Function::Floor
+                | Function::Ceil | Function::Round => {
+            let arg = args.remove(0);
+            let f: fn(f64) -> f64 = match function {
+                Function::Log   => f64::ln,
+                Function::Log10 => f64::log10,
+                Function::Exp   => f64::exp,
+                Function::Sqrt  => f64::sqrt,
+                Function::Floor => f64::floor,
+                Function::Ceil  => f64::ceil,
+                _               => f64::round,
+            };
+            let values = arg.values.into_float()?.into_iter().map(f).collect();
+            Ok(Column { values: Values::Float(values), nulls: arg.nulls })
+        }
+        Function::Abs => {
+            let mut arg = args.remove(0);
+            arg.values = match arg.values {
+                Values::Integer(values) => Values::Integer(values.iter().zip(arg.nulls.iter_mut())
+                    .map(|(&i, null)| checked(i.checked_abs(), null)).collect()),
+                values => Values::Float(values.into_float()?.iter().map(|x| x.abs()).collect()),
+            };
+            Ok(arg)
+        }
+        Function::Pow | Function::Min | Function::Max => {
+            let nulls = any_null(&args);
+            let rhs = args.pop().unwrap().values;
+            let lhs = args.pop().unwrap().values;
+            let values = match (function, lhs, rhs) {
+                (Function::Min, Values::Integer(l), Values::Integer(r)) => {
+                    Values::Integer(l.iter().zip(&r).map(|(&l, &r)| l.min(r)).collect())
+                }
+                (Function::Max, Values::Integer(l), Values::Integer(r)) => {
+                    Values::Integer(l.iter().zip(&r).map(|(&l, &r)| l.max(r)).collect())
+                }
+                (function, l, r) => Values::Float(l.into_float()?.iter().zip(&r.into_float()?)
+                    .map(|(&l, &r)| match function {
+                        Function::Pow => l.powf(r),
+                        Function::Min => l.min(r),
+                        _             => l.max(r),
+                    }).collect()),
+            };
+            Ok(Column { values, nulls })
+        }
+        Function::If => {
+            let otherwise = args.pop().unwrap();
+            let then = args.pop().unwrap();
+            let condition = args.pop().unwrap();
+            let mask = match condition.values {
+                Values::Boolean(ref values) => values,
+                _ => { return Err(unchecked()); }
+            };
+            let nulls = mask.iter().enumerate().map(|(i, &m)| {
+                condition.nulls[i] || if m { then.nulls[i] } else { otherwise.nulls[i] }
+            }).collect();
+            Ok(Column { values: then.values.select(mask, otherwise.values)?, nulls })
+        }
+        Function::Coalesce => {
+            let other = args.pop().unwrap();
+            let first = args.pop().unwrap();
+            let mask: Vec<bool> = first.nulls.iter().map(|&null| !null).collect();
+            let nulls = first.nulls.iter().zip(&other.nulls).map(|(&a, &b)| a && b).collect();
+            Ok(Column { values: first.values.select(&mask, other.values)?, nulls })
+        }
+        Function::IsNull => {
+            let arg = args.remove(0);
+            Ok(Column::new(Values::Boolean(arg.nulls)))
+        }
+        Function::Concat => {
+            let nulls = any_null(&args);
+            let mut args = args.into_iter().map(|arg| arg.values.into_text());
+            let first = args.next().unwrap();
+            let values = args.fold(first, |acc, arg| {
+                acc.into_iter().zip(arg).map(|(a, b)| a + &b).collect()
+            });
+            Ok(Column { values: Values::Text(values), nulls })
+        }
+        Function::Len | Function::Upper | Function::Lower | Function::Trim => {
+            let arg = args.remove(0);
+            let values = match arg.values {
+                Values::Text(values) => values,
+                _ => { return Err(unchecked()); }
+            };
+            let values = match function {
+                Function::Len   => Values::Integer(values.iter()
+                    .map(|s| s.chars().count() as i64).collect()),
+                Function::Upper => Values::Text(values.iter().map(|s| s.to_uppercase()).collect()),
+                Function::Lower => Values::Text(values.iter().map(|s| s.to_lowercase()).collect()),
+                _               => Values::Text(values.iter().map(|s| s.trim().to_string())
+                    .collect()),
+            };
+            Ok(Column { values, nulls: arg.nulls })
+        }
+    }
+}
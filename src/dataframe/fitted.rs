@@ -0,0 +1,96 @@
+//! Parameters of stateful transforms learned from the data they are fitted to
+
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use serde_json;
+use toml;
+
+use errors::*;
+
+/// Parameters learned by fitting the stateful transforms (normalization, scaling, one-hot
+/// vectorization and imputation) of a configuration to a dataframe. A fitted pipeline can be
+/// saved, and applied when loading other data so that it is transformed consistently with the
+/// data the pipeline was fitted to.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct FittedPipeline {
+    /// Fitted parameters of each stateful transform, in transform order
+    pub transforms: Vec<FittedTransform>,
+}
+
+impl FittedPipeline {
+    /// Fitted parameters of the transform with the specified target name, if any
+    pub fn params(&self, target_name: &str) -> Option<&FittedParams> {
+        self.transforms.iter().find(|transform| transform.target_name == target_name)
+            .map(|transform| &transform.params)
+    }
+
+    /// Load a fitted pipeline from a JSON or TOML file
+    pub fn load(path: &Path) -> Result<FittedPipeline> {
+        let mut s = String::new();
+        File::open(path).and_then(|mut f| f.read_to_string(&mut s)).chain_err(|| format!(
+            "unable to read fitted pipeline {}", path.display()))?;
+        if is_json(path) {
+            serde_json::from_str(&s).chain_err(|| "error parsing fitted pipeline as JSON")
+        } else {
+            toml::from_str(&s).chain_err(|| "error parsing fitted pipeline as TOML")
+        }
+    }
+
+    /// Save this fitted pipeline to a file, as JSON if the file has a ".json" extension and as
+    /// TOML otherwise
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let s = if is_json(path) {
+            serde_json::to_string_pretty(self).chain_err(|| "error serializing fitted pipeline")?
+        } else {
+            toml::to_string(self).chain_err(|| "error serializing fitted pipeline")?
+        };
+        File::create(path).and_then(|mut f| f.write_all(s.as_bytes())).chain_err(|| format!(
+            "unable to write fitted pipeline {}", path.display()))
+    }
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+}
+
+/// Fitted parameters of a single transform
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FittedTransform {
+    /// Target name of the transform
+    pub target_name: String,
+    /// Parameters learned by the transform
+    pub params: FittedParams,
+}
+
+/// Parameters learned by a stateful transform
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "method")]
+pub enum FittedParams {
+    /// Mean and standard deviation of the values of a normalized field
+    Normalize {
+        /// Mean of the (present) source values
+        mean: f64,
+        /// Standard deviation of the (present) source values
+        stdev: f64,
+    },
+    /// Range of the values of a scaled field
+    Scale {
+        /// Minimum (present) source value
+        min: f64,
+        /// Maximum (present) source value
+        max: f64,
+    },
+    /// Categories of a one-hot vectorized field
+    VectorizeOneHot {
+        /// Categories, in the order of the generated fields. Values which are not one of these
+        /// categories are encoded with all generated fields off
+        categories: Vec<String>,
+    },
+    /// Value used to replace the missing values of an imputed field
+    Impute {
+        /// Replacement value (in unparsed string form), or `None` for forward-fill imputation
+        fill: Option<String>,
+    },
+}
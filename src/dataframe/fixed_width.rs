@@ -0,0 +1,75 @@
+//! Fixed-width text source reading
+
+use std::cmp::min;
+use std::io::BufRead;
+
+use csv::ByteRecord;
+
+use errors::*;
+
+/// Location of a single column within the lines of a fixed-width source file
+#[derive(Debug, Clone)]
+pub struct FixedWidthColumn {
+    /// Byte offset of the start of the column
+    pub start: usize,
+    /// Width of the column, in bytes
+    pub width: usize,
+    /// Whether or not to trim surrounding whitespace from column values
+    pub trim: bool,
+}
+
+/// Reader which splits each line of a fixed-width source into a record with one entry per column
+pub struct FixedWidthReader<R> {
+    reader: R,
+    columns: Vec<FixedWidthColumn>,
+    line: Vec<u8>,
+}
+impl<R: BufRead> FixedWidthReader<R> {
+    /// Create a new fixed-width reader reading the specified columns
+    pub fn new(reader: R, columns: Vec<FixedWidthColumn>) -> FixedWidthReader<R> {
+        FixedWidthReader {
+            reader,
+            columns,
+            line: Vec::new(),
+        }
+    }
+
+    fn split_line(&self) -> ByteRecord {
+        let mut record = ByteRecord::new();
+        for column in &self.columns {
+            // lines shorter than the column specification result in truncated (or empty) values
+            let start = min(column.start, self.line.len());
+            let end = min(column.start + column.width, self.line.len());
+            let value = &self.line[start..end];
+            record.push_field(if column.trim { trim(value) } else { value });
+        }
+        record
+    }
+}
+impl<R: BufRead> Iterator for FixedWidthReader<R> {
+    type Item = Result<ByteRecord>;
+
+    fn next(&mut self) -> Option<Result<ByteRecord>> {
+        loop {
+            self.line.clear();
+            match self.reader.read_until(b'\n', &mut self.line) {
+                Ok(0) => { return None; }
+                Ok(_) => {}
+                Err(e) => { return Some(Err(e).chain_err(|| "error reading fixed-width line")); }
+            }
+            while let Some(&b'\n') | Some(&b'\r') = self.line.last() {
+                self.line.pop();
+            }
+            // skip blank lines
+            if !self.line.is_empty() {
+                return Some(Ok(self.split_line()));
+            }
+        }
+    }
+}
+
+fn trim(bytes: &[u8]) -> &[u8] {
+    let start = bytes.iter().position(|b| !b.is_ascii_whitespace()).unwrap_or(bytes.len());
+    let end = bytes.iter().rposition(|b| !b.is_ascii_whitespace()).map_or(start, |i| i + 1);
+    &bytes[start..end]
+}
@@ -0,0 +1,130 @@
+//! Inference of source file field specifications from a sample of a delimited source file
+
+use std::path::Path;
+
+use csv;
+
+use dataframe::compression;
+use dataframe::config::{Field, FieldType, SourceFile};
+use dataframe::temporal;
+
+use errors::*;
+
+/// Number of rows sampled from the source file when inferring field types
+pub const SAMPLE_ROWS: usize = 1000;
+
+/// Source values which are taken to denote a missing value when inferring field types
+const NULL_TOKENS: &[&str] = &["", "NA", "N/A", "NULL", "null", "None"];
+
+/// Field types which are considered during inference, in order of preference
+const CANDIDATE_TYPES: &[FieldType] = &[FieldType::Boolean, FieldType::Unsigned,
+    FieldType::Signed, FieldType::Float, FieldType::Date, FieldType::DateTime,
+    FieldType::Duration, FieldType::Text];
+
+/// Infer the field specifications of a delimited source file (with a header row) from a sample of
+/// its rows. Source file names are resolved relative to the configuration file, so the source is
+/// named by its file name alone, for a configuration saved in the same directory.
+pub fn infer_source_file(path: &Path, delimiter: u8) -> Result<SourceFile> {
+    let name = path.file_name().and_then(|name| name.to_str()).ok_or(Error::from_kind(
+        ErrorKind::DataConfigError("unable to convert file name to str".to_string())))?
+        .to_string();
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .from_reader(compression::open(path, None)?);
+    let headers = reader.headers().chain_err(|| "unable to parse CSV headers")?.clone();
+
+    let mut guesses: Vec<FieldGuess> = headers.iter().map(|_| FieldGuess::new()).collect();
+    for record in reader.records().take(SAMPLE_ROWS) {
+        let record = record.chain_err(|| format!("error reading CSV record from {}", path.display()))?;
+        for (guess, value) in guesses.iter_mut().zip(record.iter()) {
+            guess.observe(value);
+        }
+    }
+
+    Ok(SourceFile {
+        name,
+        filename_field: None,
+        join: None,
+        format: None,
+        delimiter: if delimiter == b',' { None } else { Some((delimiter as char).to_string()) },
+        has_headers: None,
+        sheet: None,
+        header_row: None,
+        fields: headers.iter().zip(guesses).map(|(header, guess)| guess.field(header)).collect(),
+        filters: None,
+        compression: None,
+        query: None,
+    })
+}
+
+/// Field type and missing value tokens consistent with the values of a field observed so far
+struct FieldGuess {
+    candidates: Vec<FieldType>,
+    null_values: Vec<String>,
+    has_values: bool,
+}
+
+impl FieldGuess {
+    fn new() -> FieldGuess {
+        FieldGuess {
+            candidates: CANDIDATE_TYPES.to_vec(),
+            null_values: vec![],
+            has_values: false,
+        }
+    }
+
+    fn observe(&mut self, value: &str) {
+        if NULL_TOKENS.contains(&value) {
+            if !self.null_values.iter().any(|null_value| null_value == value) {
+                self.null_values.push(value.to_string());
+            }
+        } else {
+            self.candidates.retain(|field_type| parses_as(value, *field_type));
+            self.has_values = true;
+        }
+    }
+
+    fn field(self, source_name: &str) -> Field {
+        // text always parses, so is always a remaining candidate (and fields without any
+        // non-missing values are taken to be text)
+        let field_type = if self.has_values { self.candidates[0] } else { FieldType::Text };
+        let mut null_values = self.null_values;
+        if field_type == FieldType::Text {
+            // tokens such as "None" or "NA" may well be values of text fields
+            null_values.retain(|value| value.is_empty());
+        }
+        null_values.sort_by_key(|value| NULL_TOKENS.iter().position(|token| token == value));
+        // empty strings are missing values of non-text fields by default
+        let null_values = if null_values.is_empty()
+                || (field_type != FieldType::Text && null_values == [""]) {
+            None
+        } else {
+            Some(null_values)
+        };
+        Field {
+            source_name: source_name.to_string(),
+            source_index: None,
+            start: None,
+            width: None,
+            trim: None,
+            target_name: None,
+            field_type,
+            add_to_frame: None,
+            format: None,
+            null_values,
+        }
+    }
+}
+
+fn parses_as(value: &str, field_type: FieldType) -> bool {
+    match field_type {
+        FieldType::Boolean  => value.parse::<bool>().is_ok(),
+        FieldType::Unsigned => value.parse::<u64>().is_ok(),
+        FieldType::Signed   => value.parse::<i64>().is_ok(),
+        FieldType::Float    => value.parse::<f64>().is_ok(),
+        FieldType::Date     => temporal::parse_date(value, None).is_ok(),
+        FieldType::DateTime => temporal::parse_datetime(value, None).is_ok(),
+        FieldType::Duration => temporal::parse_duration(value).is_ok(),
+        FieldType::Text     => true,
+    }
+}
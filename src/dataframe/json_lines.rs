@@ -0,0 +1,81 @@
+//! JSON Lines (newline-delimited JSON) source reading
+
+use std::io::BufRead;
+
+use csv::ByteRecord;
+use serde_json::{self, Value};
+
+use errors::*;
+
+/// Reader which parses each line of a JSON Lines source as a JSON object, and extracts a record
+/// with one entry per field path
+pub struct JsonLinesReader<R> {
+    reader: R,
+    paths: Vec<String>,
+    line: String,
+    linenum: usize,
+}
+impl<R: BufRead> JsonLinesReader<R> {
+    /// Create a new JSON Lines reader, extracting the values at the specified paths. Paths can
+    /// either be JSON pointers (e.g. "/user/address/0/city") or dotted paths (e.g.
+    /// "user.address.0.city").
+    pub fn new(reader: R, paths: Vec<String>) -> JsonLinesReader<R> {
+        JsonLinesReader {
+            reader,
+            paths,
+            line: String::new(),
+            linenum: 0,
+        }
+    }
+
+    fn extract(&self, value: &Value) -> ByteRecord {
+        let mut record = ByteRecord::new();
+        for path in &self.paths {
+            match lookup(value, path) {
+                // missing and null values are stored as empty fields
+                None | Some(Value::Null) => { record.push_field(b""); }
+                Some(Value::String(s)) => { record.push_field(s.as_bytes()); }
+                Some(other) => { record.push_field(other.to_string().as_bytes()); }
+            }
+        }
+        record
+    }
+}
+impl<R: BufRead> Iterator for JsonLinesReader<R> {
+    type Item = Result<ByteRecord>;
+
+    fn next(&mut self) -> Option<Result<ByteRecord>> {
+        loop {
+            self.line.clear();
+            self.linenum += 1;
+            match self.reader.read_line(&mut self.line) {
+                Ok(0) => { return None; }
+                Ok(_) => {}
+                Err(e) => { return Some(Err(e).chain_err(|| "error reading JSON line")); }
+            }
+            // skip blank lines
+            if self.line.trim().is_empty() {
+                continue;
+            }
+            return Some(match serde_json::from_str::<Value>(&self.line) {
+                Ok(value) => Ok(self.extract(&value)),
+                Err(e) => Err(e).chain_err(|| format!("error parsing JSON on line {}",
+                    self.linenum)),
+            });
+        }
+    }
+}
+
+/// Find the value at the given JSON pointer or dotted path
+fn lookup<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    if path.starts_with('/') {
+        return value.pointer(path);
+    }
+    path.split('.').try_fold(value, |curr, segment| {
+        match *curr {
+            Value::Object(ref map) => map.get(segment),
+            Value::Array(ref vec) => segment.parse::<usize>().ok().and_then(|i| vec.get(i)),
+            _ => None,
+        }
+    })
+}
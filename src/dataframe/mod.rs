@@ -1,8 +1,24 @@
 //! Dataframe
 
+mod columnar;
 mod convert;
+mod expression;
+mod compression;
+pub use self::compression::Compression;
+mod fitted;
+pub use self::fitted::{FittedParams, FittedPipeline, FittedTransform};
+mod fixed_width;
+mod infer;
+mod json_lines;
+mod output;
+pub use self::output::CsvFormat;
+mod spreadsheet;
+mod sparse;
+pub use self::sparse::{SparseMatrix, SparseVec};
+mod sqlite;
+mod temporal;
 pub mod config;
-pub use self::config::{DataConfig, FieldType};
+pub use self::config::{DataConfig, FieldType, SourceFormat, OutputFormat, JoinMethod};
 
 mod datastore;
 pub use self::datastore::DataStore;
@@ -11,4 +27,4 @@ mod dataframe;
 pub use self::dataframe::DataFrame;
 
 mod transform;
-pub use self::transform::TransformFields;
+pub use self::transform::{FitTransform, TransformFields};
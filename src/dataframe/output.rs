@@ -0,0 +1,122 @@
+//! Writing of dataframe data to delimited text and LibSVM-format text
+
+use std::io::Write;
+
+use csv;
+
+use dataframe::FieldType;
+use dataframe::datastore::{DataStore, FieldInfo};
+use dataframe::sparse::SparseMatrix;
+use dataframe::temporal;
+
+use errors::*;
+
+/// Format of delimited text written from a dataframe
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvFormat {
+    /// Delimiter between fields
+    pub delimiter: u8,
+    /// Whether or not to write a header row of field names
+    pub has_headers: bool,
+    /// Number of decimal places written for floating-point values. If `None`, floating-point
+    /// values are written with as many digits as necessary to represent them exactly
+    pub float_precision: Option<usize>,
+    /// Value written for missing values
+    pub null_value: String,
+}
+
+impl Default for CsvFormat {
+    /// Comma-delimited, with a header row, exact floating-point values, and missing values
+    /// written as empty strings
+    fn default() -> CsvFormat {
+        CsvFormat {
+            delimiter: b',',
+            has_headers: true,
+            float_precision: None,
+            null_value: String::new(),
+        }
+    }
+}
+
+/// Write all fields of a data store, in field order, as delimited text. Dates and date-times are
+/// written in ISO 8601 format, and durations in "HH:MM:SS" format.
+pub fn write_csv<W: Write>(data: &DataStore, writer: W, format: &CsvFormat) -> Result<()> {
+    if !data.is_homogeneous() {
+        return Err(Error::from_kind(ErrorKind::DataFrameError(
+            "DataFrame columns are not same length".to_string())));
+    }
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(format.delimiter)
+        .from_writer(writer);
+    let fields = data.fields();
+    if format.has_headers {
+        writer.write_record(fields.iter().map(|finfo| &finfo.name))
+            .chain_err(|| "error writing CSV headers")?;
+    }
+    let columns: Vec<Vec<String>> = fields.iter()
+        .map(|finfo| field_strings(data, finfo, format)).collect();
+    for row in 0..data.nrows() {
+        writer.write_record(columns.iter().map(|column| &column[row]))
+            .chain_err(|| "error writing CSV record")?;
+    }
+    writer.flush().chain_err(|| "error writing CSV record")?;
+    Ok(())
+}
+
+/// Values of a field formatted for writing
+fn field_strings(data: &DataStore, finfo: &FieldInfo, format: &CsvFormat) -> Vec<String> {
+    let name = &finfo.name;
+    let mut values: Vec<String> = match finfo.ty {
+        FieldType::Unsigned => data.get_unsigned_field(name).expect("datastore inconsistent")
+            .iter().map(|u| u.to_string()).collect(),
+        FieldType::Signed   => data.get_signed_field(name).expect("datastore inconsistent")
+            .iter().map(|s| s.to_string()).collect(),
+        FieldType::Text     => data.get_text_field(name).expect("datastore inconsistent")
+            .clone(),
+        FieldType::Boolean  => data.get_boolean_field(name).expect("datastore inconsistent")
+            .iter().map(|b| b.to_string()).collect(),
+        FieldType::Float    => data.get_float_values(name).expect("datastore inconsistent")
+            .iter().map(|f| match format.float_precision {
+                Some(precision) => format!("{:.*}", precision, f),
+                None            => f.to_string(),
+            }).collect(),
+        FieldType::Date     => data.get_date_field(name).expect("datastore inconsistent")
+            .iter().map(|d| temporal::format_date(d, None)).collect(),
+        FieldType::DateTime => data.get_datetime_field(name).expect("datastore inconsistent")
+            .iter().map(|dt| temporal::format_datetime(dt, None)).collect(),
+        FieldType::Duration => data.get_duration_field(name).expect("datastore inconsistent")
+            .iter().map(temporal::format_duration).collect(),
+    };
+    if let Some(mask) = data.get_null_mask(name) {
+        for (value, &null) in values.iter_mut().zip(mask) {
+            if null {
+                *value = format.null_value.clone();
+            }
+        }
+    }
+    values
+}
+
+/// Write labels and sparse features as LibSVM-format text: one line per row, with the label
+/// followed by space-separated "index:value" pairs of the row's stored features (with 1-based
+/// column indices). Stored NaN values (missing values) are omitted.
+pub fn write_libsvm<W: Write>(labels: &[f64], features: &SparseMatrix, mut writer: W)
+        -> Result<()> {
+    for (row, label) in labels.iter().enumerate() {
+        if label.is_nan() {
+            return Err(Error::from_kind(ErrorKind::DataFrameError(
+                format!("missing label value in row {}", row))));
+        }
+        let mut line = label.to_string();
+        let (indices, values) = features.row(row);
+        for (col, value) in indices.iter().zip(values) {
+            if !value.is_nan() {
+                line.push_str(&format!(" {}:{}", col + 1, value));
+            }
+        }
+        line.push('\n');
+        writer.write_all(line.as_bytes()).chain_err(|| "error writing LibSVM record")?;
+    }
+    writer.flush().chain_err(|| "error writing LibSVM record")?;
+    Ok(())
+}
@@ -0,0 +1,167 @@
+//! Sparse storage of floating-point fields, and sparse matrices
+
+use std::sync::OnceLock;
+
+/// Floating-point field values stored sparsely, as a default value and the rows (in increasing
+/// order) whose values differ from the default. Used for the outputs of vectorization transforms,
+/// where most values of each generated field are the same.
+#[derive(Debug, Clone)]
+pub struct SparseVec {
+    len: usize,
+    default: f64,
+    entries: Vec<(usize, f64)>,
+    dense: OnceLock<Vec<f64>>,
+}
+impl SparseVec {
+    /// Create a new sparse vector of the specified length, with all values set to a default value
+    pub fn new(len: usize, default: f64) -> SparseVec {
+        SparseVec {
+            len,
+            default,
+            entries: Vec::new(),
+            dense: OnceLock::new(),
+        }
+    }
+
+    /// Set the value of a row. Rows must be set in increasing order.
+    pub fn set(&mut self, row: usize, value: f64) {
+        debug_assert!(row < self.len && self.entries.last().is_none_or(|&(last, _)| last < row));
+        if value != self.default {
+            self.entries.push((row, value));
+            self.dense = OnceLock::new();
+        }
+    }
+
+    /// Number of values (including default values)
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    /// Whether or not there are no values
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Value of rows which aren't stored explicitly
+    pub fn default_value(&self) -> f64 {
+        self.default
+    }
+    /// Rows (in increasing order) and values which differ from the default value
+    pub fn entries(&self) -> &[(usize, f64)] {
+        &self.entries
+    }
+
+    /// Retrieve the value of a row
+    pub fn get(&self, row: usize) -> f64 {
+        match self.entries.binary_search_by_key(&row, |&(r, _)| r) {
+            Ok(i)  => self.entries[i].1,
+            Err(_) => self.default,
+        }
+    }
+
+    /// Convert into a dense vector of values
+    pub fn to_dense(&self) -> Vec<f64> {
+        let mut values = vec![self.default; self.len];
+        for &(row, value) in &self.entries {
+            values[row] = value;
+        }
+        values
+    }
+    /// Dense vector of values, generated the first time it is requested and kept until the
+    /// sparse vector is modified (use `to_dense` for a temporary dense vector)
+    pub fn dense(&self) -> &Vec<f64> {
+        self.dense.get_or_init(|| self.to_dense())
+    }
+
+    /// Append the values of another sparse vector
+    pub fn append(&mut self, other: SparseVec) {
+        let offset = self.len;
+        if other.default == self.default {
+            self.entries.extend(other.entries.into_iter()
+                .map(|(row, value)| (offset + row, value)));
+        } else {
+            // the default rows of the other vector now need explicit values
+            let default = self.default;
+            self.entries.extend(other.to_dense().into_iter().enumerate()
+                .filter(|&(_, value)| value != default)
+                .map(|(row, value)| (offset + row, value)));
+        }
+        self.len += other.len;
+        self.dense = OnceLock::new();
+    }
+
+    /// Extend by `n` rows of the specified value
+    pub fn extend(&mut self, value: f64, n: usize) {
+        let offset = self.len;
+        self.len += n;
+        for row in offset..self.len {
+            self.set(row, value);
+        }
+        self.dense = OnceLock::new();
+    }
+
+    /// Generate a new sparse vector from the specified rows of this vector, filling rows of `None`
+    /// with the specified value
+    pub fn gather(&self, rows: &[Option<usize>], fill: f64) -> SparseVec {
+        let mut gathered = SparseVec::new(rows.len(), self.default);
+        for (i, row) in rows.iter().enumerate() {
+            gathered.set(i, row.map_or(fill, |row| self.get(row)));
+        }
+        gathered
+    }
+}
+
+/// Sparse matrix in compressed sparse row (CSR) format. Values not stored are zero.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseMatrix {
+    /// Number of rows
+    pub nrows: usize,
+    /// Number of columns
+    pub ncols: usize,
+    /// Offset into `indices` and `values` of the first stored value of each row, followed by the
+    /// total number of stored values
+    pub indptr: Vec<usize>,
+    /// Column index of each stored value
+    pub indices: Vec<usize>,
+    /// Stored values, in row order (and in column order within each row)
+    pub values: Vec<f64>,
+}
+impl SparseMatrix {
+    /// Build a sparse matrix from the stored (column index, value) pairs of each row, in column
+    /// order
+    pub fn from_rows(rows: Vec<Vec<(usize, f64)>>, ncols: usize) -> SparseMatrix {
+        let mut indptr = Vec::with_capacity(rows.len() + 1);
+        let mut indices = Vec::new();
+        let mut values = Vec::new();
+        indptr.push(0);
+        for row in &rows {
+            for &(col, value) in row {
+                indices.push(col);
+                values.push(value);
+            }
+            indptr.push(indices.len());
+        }
+        SparseMatrix {
+            nrows: rows.len(),
+            ncols,
+            indptr,
+            indices,
+            values,
+        }
+    }
+
+    /// Number of stored values
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Column indices and values stored in a row
+    pub fn row(&self, row: usize) -> (&[usize], &[f64]) {
+        let (start, end) = (self.indptr[row], self.indptr[row + 1]);
+        (&self.indices[start..end], &self.values[start..end])
+    }
+
+    /// Retrieve the value at the specified row and column
+    pub fn get(&self, row: usize, col: usize) -> f64 {
+        let (indices, values) = self.row(row);
+        indices.binary_search(&col).map_or(0.0, |i| values[i])
+    }
+}
@@ -0,0 +1,98 @@
+//! Spreadsheet (OpenDocument, Excel) source reading
+
+use std::io::{Cursor, Read};
+
+use calamine::{open_workbook_auto_from_rs, Data, DataType, Range, Reader, Rows};
+use chrono::NaiveTime;
+use csv::ByteRecord;
+
+use errors::*;
+
+use dataframe::temporal;
+
+/// Load a worksheet from spreadsheet data (.ods, .xlsx, .xls, or .xlsb formats). If no sheet
+/// name is specified, the first worksheet is used.
+pub fn read_sheet<R: Read>(mut reader: R, sheet: Option<&str>) -> Result<Range<Data>> {
+    // spreadsheet formats require seeking, so read the whole file into memory
+    let mut buffer = Vec::new();
+    reader.read_to_end(&mut buffer).chain_err(|| "unable to read spreadsheet")?;
+    let mut workbook = open_workbook_auto_from_rs(Cursor::new(buffer))
+        .chain_err(|| "unable to open spreadsheet")?;
+    match sheet {
+        Some(sheet) => {
+            workbook.worksheet_range(sheet).chain_err(|| format!("unable to read worksheet {}",
+                sheet))
+        }
+        None => {
+            workbook.worksheet_range_at(0)
+                .ok_or_else(|| Error::from_kind(ErrorKind::DataFrameError(
+                    "spreadsheet contains no worksheets".to_string())))?
+                .chain_err(|| "unable to read worksheet")
+        }
+    }
+}
+
+/// Iterator over the rows of a worksheet, producing one record per row. Record fields are
+/// positioned relative to the first column of the worksheet (column 'A').
+pub struct SheetRecords<'a> {
+    rows: Rows<'a, Data>,
+    first_col: usize,
+}
+impl<'a> SheetRecords<'a> {
+    /// Create a new iterator over the worksheet rows, beginning at the specified (zero-based) row
+    /// index. If no row index is specified, begins at the first non-empty row.
+    pub fn new(range: &'a Range<Data>, first_row: Option<usize>) -> SheetRecords<'a> {
+        let (start_row, start_col) = range.start().unwrap_or((0, 0));
+        let mut rows = range.rows();
+        if let Some(first_row) = first_row {
+            for _ in 0..first_row.saturating_sub(start_row as usize) {
+                rows.next();
+            }
+        }
+        SheetRecords {
+            rows,
+            first_col: start_col as usize,
+        }
+    }
+}
+impl<'a> Iterator for SheetRecords<'a> {
+    type Item = Result<ByteRecord>;
+
+    fn next(&mut self) -> Option<Result<ByteRecord>> {
+        self.rows.next().map(|row| {
+            let mut record = ByteRecord::new();
+            for _ in 0..self.first_col {
+                record.push_field(b"");
+            }
+            for cell in row {
+                if let Data::Error(ref e) = *cell {
+                    return Err(Error::from_kind(ErrorKind::DataFrameError(
+                        format!("spreadsheet cell error: {}", e))));
+                }
+                record.push_field(cell_to_string(cell).as_bytes());
+            }
+            Ok(record)
+        })
+    }
+}
+
+/// Text form of a cell value. Date and time cells are converted to ISO 8601 dates (for cells
+/// without a time of day) or date-times, and duration cells to the form "HH:MM:SS".
+fn cell_to_string(cell: &Data) -> String {
+    match *cell {
+        Data::DateTime(ref dt) if dt.is_duration() => {
+            cell.as_duration().map(|d| temporal::format_duration(&d))
+        }
+        Data::DateTime(_) => {
+            cell.as_datetime().map(|dt| {
+                if dt.time() == NaiveTime::MIN {
+                    temporal::format_date(&dt.date(), None)
+                } else {
+                    temporal::format_datetime(&dt, None)
+                }
+            })
+        }
+        Data::DurationIso(_) => cell.as_duration().map(|d| temporal::format_duration(&d)),
+        _ => None,
+    }.unwrap_or_else(|| cell.to_string())
+}
@@ -0,0 +1,192 @@
+//! SQLite source querying and dataframe writing
+
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read};
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use csv::ByteRecord;
+use rusqlite::{self, Connection, OpenFlags};
+use rusqlite::types::{Value, ValueRef};
+
+use dataframe::FieldType;
+use dataframe::config::Field;
+use dataframe::datastore::DataStore;
+use dataframe::temporal;
+
+use errors::*;
+
+/// Run a query against the SQLite database at the specified path, and extract a record for each
+/// result row with one entry per field. Result columns are found by the source name of each
+/// field. Integers are converted to booleans for boolean fields, and NULL values are stored as
+/// empty fields.
+pub fn query_records(path: &Path, query: &str, fields: &[Field]) -> Result<Vec<ByteRecord>> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .chain_err(|| format!("unable to open SQLite database {}", path.display()))?;
+    let mut stmt = conn.prepare(query).chain_err(|| format!("invalid SQLite query: {}", query))?;
+    let mut indices = vec![];
+    for field in fields {
+        indices.push(stmt.column_index(&field.source_name).chain_err(|| format!(
+            "column {} not found in SQLite query results", field.source_name))?);
+    }
+
+    let mut records = vec![];
+    let mut rows = stmt.query([]).chain_err(|| format!("error running SQLite query: {}", query))?;
+    while let Some(row) = rows.next().chain_err(|| "error reading SQLite query results")? {
+        let mut record = ByteRecord::new();
+        for (field, &index) in fields.iter().zip(&indices) {
+            let value = row.get_ref(index).chain_err(|| "error reading SQLite query results")?;
+            match value {
+                ValueRef::Null => { record.push_field(b""); }
+                ValueRef::Integer(i) if field.field_type == FieldType::Boolean => {
+                    record.push_field(if i != 0 { b"true" } else { b"false" });
+                }
+                ValueRef::Integer(i) => { record.push_field(i.to_string().as_bytes()); }
+                ValueRef::Real(f) => { record.push_field(f.to_string().as_bytes()); }
+                ValueRef::Text(bytes) | ValueRef::Blob(bytes) => { record.push_field(bytes); }
+            }
+        }
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// Run a query against a SQLite database provided by a reader. The database is copied to a
+/// temporary file, since SQLite can only query files (or its own in-memory databases).
+pub fn query_reader_records<R: Read>(mut reader: R, query: &str, fields: &[Field])
+        -> Result<Vec<ByteRecord>> {
+    let (temp_file, mut file) = TempFile::create()?;
+    io::copy(&mut reader, &mut file).chain_err(||
+        "error copying SQLite database to temporary file")?;
+    drop(file);
+    query_records(&temp_file.path, query, fields)
+}
+
+/// Maximum number of temporary file names tried before giving up
+const TEMP_ATTEMPTS: usize = 100;
+
+/// Temporary file, which is removed when dropped (including when unwinding from a panic)
+struct TempFile {
+    path: PathBuf,
+}
+impl TempFile {
+    /// Create a new temporary file, readable only by the current user. The file is created
+    /// exclusively, so existing files (or symbolic links) at the chosen path are never opened.
+    fn create() -> Result<(TempFile, File)> {
+        static TEMP_COUNT: AtomicUsize = AtomicUsize::new(0);
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.subsec_nanos());
+        for _ in 0..TEMP_ATTEMPTS {
+            let path = env::temp_dir().join(format!("etl-{}-{}-{}.sqlite", process::id(), nanos,
+                TEMP_COUNT.fetch_add(1, Ordering::SeqCst)));
+            let mut options = OpenOptions::new();
+            options.write(true).create_new(true);
+            #[cfg(unix)]
+            options.mode(0o600);
+            match options.open(&path) {
+                Ok(file) => { return Ok((TempFile { path }, file)); }
+                Err(ref e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+                Err(e) => {
+                    return Err(e).chain_err(|| format!("unable to create temporary file {}",
+                        path.display()));
+                }
+            }
+        }
+        Err(Error::from_kind(ErrorKind::DataFrameError(
+            "unable to create a temporary file for SQLite database".to_string())))
+    }
+}
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        // the query results are already read (or failed), so a leftover temporary file is harmless
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Write all fields of a data store (in field order) as a new table of the SQLite database at the
+/// specified path (creating the database if necessary). Dates and date-times are stored as ISO
+/// 8601 text, booleans as integers, durations as (floating-point) seconds, and missing values as
+/// NULL.
+pub fn write_table(data: &DataStore, path: &Path, table: &str) -> Result<()> {
+    if !data.is_homogeneous() {
+        return Err(Error::from_kind(ErrorKind::DataFrameError(
+            "DataFrame columns are not same length".to_string())));
+    }
+    let fields = data.fields();
+    if fields.is_empty() {
+        return Err(Error::from_kind(ErrorKind::DataFrameError(format!(
+            "DataFrame has no fields to write to SQLite table {}", table))));
+    }
+    let mut conn = Connection::open(path).chain_err(|| format!(
+        "unable to open SQLite database {}", path.display()))?;
+    let columns: Vec<Vec<Value>> = fields.iter().map(|finfo| {
+        let name = &finfo.name;
+        let mut values: Vec<Value> = match finfo.ty {
+            FieldType::Unsigned => data.get_unsigned_field(name).expect("datastore inconsistent")
+                .iter().map(|&u| if u > i64::MAX as u64 {
+                    Err(Error::from_kind(ErrorKind::DataFrameError(format!(
+                        "value {} of field {} is too large for SQLite", u, name))))
+                } else {
+                    Ok(Value::Integer(u as i64))
+                }).collect::<Result<_>>()?,
+            FieldType::Signed   => data.get_signed_field(name).expect("datastore inconsistent")
+                .iter().map(|&s| Value::Integer(s)).collect(),
+            FieldType::Text     => data.get_text_field(name).expect("datastore inconsistent")
+                .iter().map(|s| Value::Text(s.clone())).collect(),
+            FieldType::Boolean  => data.get_boolean_field(name).expect("datastore inconsistent")
+                .iter().map(|&b| Value::Integer(b as i64)).collect(),
+            FieldType::Float    => data.get_float_values(name).expect("datastore inconsistent")
+                .iter().map(|&f| Value::Real(f)).collect(),
+            FieldType::Date     => data.get_date_field(name).expect("datastore inconsistent")
+                .iter().map(|d| Value::Text(temporal::format_date(d, None))).collect(),
+            FieldType::DateTime => data.get_datetime_field(name).expect("datastore inconsistent")
+                .iter().map(|dt| Value::Text(temporal::format_datetime(dt, None))).collect(),
+            FieldType::Duration => data.get_duration_field(name).expect("datastore inconsistent")
+                .iter().map(|d| Value::Real(temporal::duration_to_seconds(d))).collect(),
+        };
+        if let Some(mask) = data.get_null_mask(name) {
+            for (value, &null) in values.iter_mut().zip(mask) {
+                if null {
+                    *value = Value::Null;
+                }
+            }
+        }
+        Ok(values)
+    }).collect::<Result<_>>()?;
+
+    let column_defs: Vec<String> = fields.iter().map(|finfo| {
+        format!("{} {}", quote_identifier(&finfo.name), sqlite_type(finfo.ty))
+    }).collect();
+    let tx = conn.transaction().chain_err(|| "error starting SQLite transaction")?;
+    tx.execute(&format!("CREATE TABLE {} ({})", quote_identifier(table), column_defs.join(", ")),
+        []).chain_err(|| format!("unable to create SQLite table {}", table))?;
+    {
+        let placeholders = vec!["?"; fields.len()].join(", ");
+        let mut insert = tx.prepare(&format!("INSERT INTO {} VALUES ({})",
+            quote_identifier(table), placeholders))
+            .chain_err(|| format!("unable to insert into SQLite table {}", table))?;
+        for row in 0..data.nrows() {
+            insert.execute(rusqlite::params_from_iter(columns.iter().map(|column| &column[row])))
+                .chain_err(|| format!("unable to insert into SQLite table {}", table))?;
+        }
+    }
+    tx.commit().chain_err(|| "error committing SQLite transaction")?;
+    Ok(())
+}
+
+/// SQLite column type used to store fields of the specified field type
+fn sqlite_type(field_type: FieldType) -> &'static str {
+    match field_type {
+        FieldType::Unsigned | FieldType::Signed | FieldType::Boolean => "INTEGER",
+        FieldType::Float | FieldType::Duration => "REAL",
+        FieldType::Text | FieldType::Date | FieldType::DateTime => "TEXT",
+    }
+}
+
+fn quote_identifier(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
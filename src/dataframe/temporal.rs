@@ -0,0 +1,151 @@
+//! Parsing, formatting and epoch conversion of date, date-time and duration values
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime};
+
+use errors::*;
+
+/// Default format of date values (ISO 8601, e.g. "2017-06-30")
+pub const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+/// Default format of date-time values (ISO 8601, e.g. "2017-06-30T14:05:00")
+pub const DEFAULT_DATETIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+
+/// Parse a date value with the specified format (or the default ISO 8601 format)
+pub fn parse_date(value_str: &str, format: Option<&str>) -> Result<NaiveDate> {
+    let format = format.unwrap_or(DEFAULT_DATE_FORMAT);
+    NaiveDate::parse_from_str(value_str, format).chain_err(|| format!(
+        "date parse error: '{}' does not match format '{}'", value_str, format))
+}
+
+/// Parse a date-time value with the specified format (or the default ISO 8601 format). Values
+/// with a UTC offset are converted to UTC. With the default format, date-only values are parsed
+/// as midnight.
+pub fn parse_datetime(value_str: &str, format: Option<&str>) -> Result<NaiveDateTime> {
+    match format {
+        Some(format) => {
+            NaiveDateTime::parse_from_str(value_str, format)
+                .or_else(|_| DateTime::parse_from_str(value_str, format)
+                    .map(|dt| dt.naive_utc()))
+                .chain_err(|| format!("date-time parse error: '{}' does not match format '{}'",
+                    value_str, format))
+        }
+        None => {
+            NaiveDateTime::parse_from_str(value_str, DEFAULT_DATETIME_FORMAT)
+                .or_else(|_| NaiveDateTime::parse_from_str(value_str, "%Y-%m-%d %H:%M:%S%.f"))
+                .or_else(|_| DateTime::parse_from_rfc3339(value_str).map(|dt| dt.naive_utc()))
+                .or_else(|_| NaiveDate::parse_from_str(value_str, DEFAULT_DATE_FORMAT)
+                    .map(|d| d.and_hms_opt(0, 0, 0).expect("midnight is valid")))
+                .chain_err(|| format!("date-time parse error: '{}' is not an ISO 8601 date-time",
+                    value_str))
+        }
+    }
+}
+
+/// Parse a duration value, either in the form "[-]HH:MM:SS[.fff]" (where hours can exceed 24) or
+/// as a (possibly fractional) number of seconds
+pub fn parse_duration(value_str: &str) -> Result<Duration> {
+    let invalid = || Error::from_kind(ErrorKind::DataFrameError(format!(
+        "duration parse error: '{}' is not of the form HH:MM:SS or a number of seconds",
+        value_str)));
+    let (negative, unsigned) = match value_str.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None       => (false, value_str),
+    };
+    let parts: Vec<&str> = unsigned.split(':').collect();
+    let seconds = match parts.len() {
+        1 => parts[0].parse::<f64>().map_err(|_| invalid())?,
+        3 => {
+            let hours = parts[0].parse::<u64>().map_err(|_| invalid())?;
+            let minutes = parts[1].parse::<u64>().map_err(|_| invalid())?;
+            let seconds = parts[2].parse::<f64>().map_err(|_| invalid())?;
+            if minutes >= 60 || !(0.0..60.0).contains(&seconds) {
+                return Err(invalid());
+            }
+            let whole = hours.checked_mul(3600).and_then(|h| h.checked_add(minutes * 60))
+                .ok_or_else(|| out_of_range(value_str))?;
+            whole as f64 + seconds
+        }
+        _ => { return Err(invalid()); }
+    };
+    if !seconds.is_finite() {
+        return Err(invalid());
+    }
+    let duration = duration_from_seconds(seconds).ok_or_else(|| out_of_range(value_str))?;
+    Ok(if negative { -duration } else { duration })
+}
+
+fn out_of_range(value_str: &str) -> Error {
+    Error::from_kind(ErrorKind::DataFrameError(format!("duration '{}' out of range", value_str)))
+}
+
+/// Format a date value with the specified format (or the default ISO 8601 format)
+pub fn format_date(date: &NaiveDate, format: Option<&str>) -> String {
+    date.format(format.unwrap_or(DEFAULT_DATE_FORMAT)).to_string()
+}
+
+/// Format a date-time value with the specified format (or the default ISO 8601 format)
+pub fn format_datetime(datetime: &NaiveDateTime, format: Option<&str>) -> String {
+    datetime.format(format.unwrap_or(DEFAULT_DATETIME_FORMAT)).to_string()
+}
+
+/// Format a duration value in the form "[-]HH:MM:SS[.fff]"
+pub fn format_duration(duration: &Duration) -> String {
+    let sign = if *duration < Duration::zero() { "-" } else { "" };
+    let duration = duration.abs();
+    let total_seconds = duration.num_seconds();
+    let nanos = duration.subsec_nanos();
+    let mut formatted = format!("{}{:02}:{:02}:{:02}", sign, total_seconds / 3600,
+        (total_seconds / 60) % 60, total_seconds % 60);
+    if nanos != 0 {
+        let fraction = format!("{:09}", nanos);
+        formatted.push('.');
+        formatted.push_str(fraction.trim_end_matches('0'));
+    }
+    formatted
+}
+
+/// Seconds since the Unix epoch of midnight on a date
+pub fn date_to_epoch(date: &NaiveDate) -> i64 {
+    date.and_hms_opt(0, 0, 0).expect("midnight is valid").and_utc().timestamp()
+}
+
+/// Seconds (including fractional seconds) since the Unix epoch of a date-time
+pub fn datetime_to_epoch(datetime: &NaiveDateTime) -> f64 {
+    let datetime = datetime.and_utc();
+    datetime.timestamp() as f64 + f64::from(datetime.timestamp_subsec_nanos()) / 1e9
+}
+
+/// Date-time at a number of seconds (including fractional seconds) since the Unix epoch
+pub fn epoch_to_datetime(seconds: f64) -> Option<NaiveDateTime> {
+    if !seconds.is_finite() {
+        return None;
+    }
+    let whole = seconds.floor();
+    let nanos = ((seconds - whole) * 1e9).round().min(999_999_999.0) as u32;
+    DateTime::from_timestamp(whole as i64, nanos).map(|dt| dt.naive_utc())
+}
+
+/// Total seconds (including fractional seconds) of a duration
+pub fn duration_to_seconds(duration: &Duration) -> f64 {
+    duration.num_seconds() as f64 + f64::from(duration.subsec_nanos()) / 1e9
+}
+
+/// Duration of a number of seconds (including fractional seconds), or `None` if the number of
+/// seconds isn't finite or is out of the range of durations
+pub fn duration_from_seconds(seconds: f64) -> Option<Duration> {
+    if !seconds.is_finite() || seconds.abs() >= i64::MAX as f64 {
+        return None;
+    }
+    let whole = seconds.trunc();
+    let nanos = ((seconds - whole) * 1e9).round() as i64;
+    Duration::try_seconds(whole as i64)?.checked_add(&Duration::nanoseconds(nanos))
+}
+
+/// Placeholder date (the Unix epoch) used for missing values
+pub fn epoch_date() -> NaiveDate {
+    NaiveDate::from_ymd_opt(1970, 1, 1).expect("epoch is valid")
+}
+
+/// Placeholder date-time (the Unix epoch) used for missing values
+pub fn epoch_datetime() -> NaiveDateTime {
+    epoch_date().and_hms_opt(0, 0, 0).expect("midnight is valid")
+}
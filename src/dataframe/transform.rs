@@ -1,15 +1,23 @@
+use std::borrow::Cow;
 use std::f64;
-use std::collections::HashMap;
+use std::fmt::Display;
+use std::collections::{HashMap, HashSet};
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 use std::ops::Shl;
 
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, Timelike, Weekday};
+
 use errors::*;
 
 use dataframe::{DataStore, FieldType};
 use dataframe::config::{ConvertConfig, MapConfig, ConcatenateConfig, VecOneHotConfig, VecHashConfig,
-    NormalizeConfig, ScaleConfig};
+    NormalizeConfig, ScaleConfig, ImputeConfig, ImputeStrategy, DatePartsConfig, DatePart,
+    ExpressionConfig};
 use dataframe::convert::convert_field;
+use dataframe::fitted::FittedParams;
+use dataframe::sparse::SparseVec;
+use dataframe::temporal;
 
 /// Trait for field transformation.
 pub trait TransformFields {
@@ -17,6 +25,44 @@ pub trait TransformFields {
     /// target field name
     fn transform_fields(&self, orig_ds: &DataStore, source_fields: &Vec<String>,
             target_name: &String) -> Result<DataStore>;
+    /// Map the values of the field(s) generated by this transformation (in the given data store)
+    /// back to values of the source field, using the fitted parameters of stateful
+    /// transformations. Fails for transformations which are not invertible.
+    fn inverse_fields(&self, _tf_ds: &DataStore, _source_fields: &[String], target_name: &str,
+            _params: Option<&FittedParams>) -> Result<DataStore> {
+        Err(Error::from_kind(ErrorKind::DataConfigError(format!(
+            "transform: {} is not invertible", target_name))))
+    }
+}
+
+/// Trait for stateful field transformations, whose parameters are learned from the data they are
+/// fitted to. The fitted parameters can be reused to transform other data consistently.
+pub trait FitTransform {
+    /// Learn the parameters of this transformation from the source fields of the given data
+    /// store
+    fn fit(&self, orig_ds: &DataStore, source_fields: &[String]) -> Result<FittedParams>;
+    /// Transform the source fields of the given data store with previously-fitted parameters,
+    /// with target field name
+    fn apply(&self, orig_ds: &DataStore, source_fields: &[String], target_name: &str,
+        params: &FittedParams) -> Result<DataStore>;
+}
+
+/// Error for fitted parameters which were learned by a different kind of transformation
+fn mismatched_params(target_name: &str) -> Error {
+    Error::from_kind(ErrorKind::DataConfigError(format!(
+        "transform: fitted parameters for {} do not match transformation method", target_name)))
+}
+
+/// Fitted floating-point parameters must be finite: they can't be saved otherwise, and can't be
+/// used to transform any data (e.g. the mean of a field with no present values)
+fn finite_params(source_field: &str, action: &str, params: &[f64]) -> Result<()> {
+    if params.iter().all(|f| f.is_finite()) {
+        Ok(())
+    } else {
+        Err(Error::from_kind(ErrorKind::DataFrameError(format!(
+            "transform: unable to fit {} to field {}: field has no finite values", action,
+            source_field))))
+    }
 }
 
 impl TransformFields for ConvertConfig {
@@ -31,7 +77,7 @@ impl TransformFields for ConvertConfig {
             .ok_or(Error::from_kind(ErrorKind::DataConfigError("bad transform call".to_string())))?;
 
         Ok(convert_field(&source_field, source_finfo.ty, target_name, self.target_type(),
-            &orig_ds)?)
+            self.format(), &orig_ds)?)
     }
 }
 
@@ -53,8 +99,35 @@ impl TransformFields for MapConfig {
         let mut tf_data = DataStore::empty();
         tf_data.merge_text(target_name, orig_ds.get_text_field(source_field).unwrap().iter()
             .map(|&ref s| self.map.get(s).unwrap_or(&self.default_value).clone()).collect())?;
+        copy_nulls(&mut tf_data, orig_ds, source_field, target_name);
         Ok(tf_data)
     }
+
+    fn inverse_fields(&self, tf_ds: &DataStore, source_fields: &[String], target_name: &str,
+            _params: Option<&FittedParams>) -> Result<DataStore> {
+        let source_field = inverse_target(source_fields, target_name)?;
+        // only one-to-one maps are invertible, with the default value distinct from mapped values
+        let mut inverse_map: HashMap<&String, &String> = HashMap::new();
+        for (key, value) in &self.map {
+            if *value == self.default_value || inverse_map.insert(value, key).is_some() {
+                return Err(Error::from_kind(ErrorKind::DataConfigError(format!(
+                    "transform: {} is not invertible (map is not one-to-one)", target_name))));
+            }
+        }
+        let values = tf_ds.get_text_field(&target_name.to_string()).ok_or_else(||
+            inverse_missing_field(target_name, FieldType::Text))?;
+
+        // values which aren't mapped from any source value (including the default) are missing
+        let mut nulls = null_mask(tf_ds, target_name);
+        let mut inv_data = DataStore::empty();
+        inv_data.merge_text(source_field, values.iter().zip(nulls.iter_mut())
+            .map(|(value, null)| match inverse_map.get(value) {
+                Some(&key) => key.clone(),
+                None       => { *null = true; String::new() }
+            }).collect())?;
+        inv_data.merge_nulls(source_field, nulls);
+        Ok(inv_data)
+    }
 }
 
 impl TransformFields for ConcatenateConfig {
@@ -91,6 +164,16 @@ impl TransformFields for ConcatenateConfig {
 
         let mut tf_data = DataStore::empty();
         tf_data.merge_text(target_name, tf_data_vec)?;
+        // concatenation is missing if any of the source values are missing
+        let mut nulls = vec![false; nrows];
+        for source_field in source_fields {
+            if let Some(mask) = orig_ds.get_null_mask(source_field) {
+                for (null, &source_null) in nulls.iter_mut().zip(mask) {
+                    *null |= source_null;
+                }
+            }
+        }
+        tf_data.merge_nulls(target_name, nulls);
         Ok(tf_data)
     }
 }
@@ -98,44 +181,112 @@ impl TransformFields for ConcatenateConfig {
 impl TransformFields for VecOneHotConfig {
     fn transform_fields(&self, orig_ds: &DataStore, source_fields: &Vec<String>,
             target_name: &String) -> Result<DataStore> {
-        if !source_fields.len() == 1 {
-            return Err(Error::from_kind(ErrorKind::DataConfigError(
-                "transform: one-hot vectorization expects only 1 source field".to_string())));
+        self.apply(orig_ds, source_fields, target_name, &self.fit(orig_ds, source_fields)?)
+    }
+
+    fn inverse_fields(&self, tf_ds: &DataStore, source_fields: &[String], target_name: &str,
+            params: Option<&FittedParams>) -> Result<DataStore> {
+        let source_field = inverse_target(source_fields, target_name)?;
+        let categories = match params {
+            Some(FittedParams::VectorizeOneHot { categories }) => categories,
+            _ => { return Err(mismatched_params(target_name)); }
+        };
+        let mut category_values: Vec<Cow<[f64]>> = Vec::new();
+        for category in categories {
+            let field_name = format!("{}_{}", target_name, category);
+            category_values.push(tf_ds.get_float_values(&field_name).ok_or_else(||
+                inverse_missing_field(&field_name, FieldType::Float))?);
         }
 
-        let source_field = source_fields.first().unwrap();
-        let source_finfo = orig_ds.get_fieldinfo(source_field)
-            .ok_or(Error::from_kind(ErrorKind::DataConfigError("bad transform call".to_string())))?;
-        if source_finfo.ty != FieldType::Text {
-            return Err(Error::from_kind(ErrorKind::DataConfigError(
-                "transform: vectorize one-hot transform requires string source values".to_string()
-            )));
+        // each row takes the category of its largest value, and is missing if no value is larger
+        // than the off value (or all values are missing)
+        let off_value = self.binary_scaling().off_value();
+        let mut values: Vec<String> = Vec::with_capacity(tf_ds.nrows());
+        let mut nulls: Vec<bool> = Vec::with_capacity(tf_ds.nrows());
+        for row in 0..tf_ds.nrows() {
+            let mut best: Option<(usize, f64)> = None;
+            for (i, category_values) in category_values.iter().enumerate() {
+                let value = category_values[row];
+                if value > best.map_or(off_value, |(_, best_value)| best_value) {
+                    best = Some((i, value));
+                }
+            }
+            values.push(best.map_or(String::new(), |(i, _)| categories[i].clone()));
+            nulls.push(best.is_none());
         }
+        let mut inv_data = DataStore::empty();
+        inv_data.merge_text(source_field, values)?;
+        inv_data.merge_nulls(source_field, nulls);
+        Ok(inv_data)
+    }
+}
 
+impl FitTransform for VecOneHotConfig {
+    fn fit(&self, orig_ds: &DataStore, source_fields: &[String]) -> Result<FittedParams> {
+        let source_field = one_hot_source(orig_ds, source_fields)?;
         let data_vec = orig_ds.get_text_field(source_field).unwrap();
-        let mut assignments: HashMap<String, usize> = HashMap::new();
-        let mut unique_values: Vec<String> = Vec::new();
-        for s in data_vec {
-            if !assignments.contains_key(s) {
-                assignments.insert(s.clone(), unique_values.len());
-                unique_values.push(s.clone());
+        let mut categories: Vec<String> = Vec::new();
+        let mut seen: HashSet<&String> = HashSet::new();
+        // missing values are not a category
+        for (i, s) in data_vec.iter().enumerate() {
+            if !orig_ds.is_null(source_field, i) && seen.insert(s) {
+                categories.push(s.clone());
             }
         }
+        Ok(FittedParams::VectorizeOneHot { categories })
+    }
+
+    fn apply(&self, orig_ds: &DataStore, source_fields: &[String], target_name: &str,
+            params: &FittedParams) -> Result<DataStore> {
+        let source_field = one_hot_source(orig_ds, source_fields)?;
+        let categories = match *params {
+            FittedParams::VectorizeOneHot { ref categories } => categories,
+            _ => { return Err(mismatched_params(target_name)); }
+        };
+        let target_name = &target_name.to_string();
+        let data_vec = orig_ds.get_text_field(source_field).unwrap();
+        let assignments: HashMap<&String, usize> = categories.iter().enumerate()
+            .map(|(i, category)| (category, i)).collect();
         let (off_value, on_value) = self.binary_scaling().values();
-        let mut onehots: Vec<Vec<f64>> = vec![vec![off_value; data_vec.len()]; unique_values.len()];
+        let mut onehots: Vec<SparseVec> =
+            vec![SparseVec::new(data_vec.len(), off_value); categories.len()];
+        // missing values (and values which aren't a fitted category) are encoded with all values
+        // off
         for (i, s) in data_vec.iter().enumerate() {
-            onehots[assignments[s]][i] = on_value;
+            if !orig_ds.is_null(source_field, i) {
+                if let Some(&category) = assignments.get(s) {
+                    onehots[category].set(i, on_value);
+                }
+            }
         }
 
         let mut tf_data = DataStore::empty();
-        for (i, val) in unique_values.iter().enumerate() {
-            tf_data.merge_float(&(target_name.clone() + &format!("_{}", val)[..]),
-                onehots[i].clone())?;
+        for (val, onehot) in categories.iter().zip(onehots) {
+            tf_data.merge_sparse_float(&(target_name.clone() + &format!("_{}", val)[..]),
+                onehot)?;
         }
         Ok(tf_data)
     }
 }
 
+/// Source field of a one-hot vectorization, which must be a single text field
+fn one_hot_source<'a>(orig_ds: &DataStore, source_fields: &'a [String]) -> Result<&'a String> {
+    if source_fields.len() != 1 {
+        return Err(Error::from_kind(ErrorKind::DataConfigError(
+            "transform: one-hot vectorization expects only 1 source field".to_string())));
+    }
+
+    let source_field = source_fields.first().unwrap();
+    let source_finfo = orig_ds.get_fieldinfo(source_field)
+        .ok_or(Error::from_kind(ErrorKind::DataConfigError("bad transform call".to_string())))?;
+    if source_finfo.ty != FieldType::Text {
+        return Err(Error::from_kind(ErrorKind::DataConfigError(
+            "transform: vectorize one-hot transform requires string source values".to_string()
+        )));
+    }
+    Ok(source_field)
+}
+
 impl TransformFields for VecHashConfig {
     fn transform_fields(&self, orig_ds: &DataStore, source_fields: &Vec<String>,
             target_name: &String) -> Result<DataStore> {
@@ -154,25 +305,133 @@ impl TransformFields for VecHashConfig {
 
         let data_vec = orig_ds.get_text_field(source_field).unwrap();
         let hash_size = self.hash_size();
-        let mut hash_vecs: Vec<Vec<f64>> = vec![vec![0.0; data_vec.len()]; hash_size as usize];
+        let mut hash_vecs: Vec<SparseVec> =
+            vec![SparseVec::new(data_vec.len(), 0.0); hash_size as usize];
         let midpoint = 1u64.shl(63);
 
+        // missing values are encoded as all zeros
         for (i, s) in data_vec.iter().enumerate() {
+            if orig_ds.is_null(source_field, i) {
+                continue;
+            }
             let mut hasher = DefaultHasher::new();
             s.hash(&mut hasher);
             let h = hasher.finish();
-            hash_vecs[(h % hash_size) as usize][i] += if h >= midpoint { 1.0 } else { -1.0 };
+            // each row has a single value, so only one bucket of each row is set
+            hash_vecs[(h % hash_size) as usize].set(i, if h >= midpoint { 1.0 } else { -1.0 });
+        }
+
+        let mut tf_data = DataStore::empty();
+        for (i, hash_vec) in hash_vecs.into_iter().enumerate() {
+            tf_data.merge_sparse_float(&(target_name.clone() + &format!("_{}", i)[..]),
+                hash_vec)?;
+        }
+        Ok(tf_data)
+    }
+}
+
+impl TransformFields for DatePartsConfig {
+    fn transform_fields(&self, orig_ds: &DataStore, source_fields: &Vec<String>,
+            target_name: &String) -> Result<DataStore> {
+        if source_fields.len() != 1 {
+            return Err(Error::from_kind(ErrorKind::DataConfigError(
+                "transform: date-part extraction expects only 1 source field".to_string())));
         }
 
+        let source_field = source_fields.first().unwrap();
+        let source_finfo = orig_ds.get_fieldinfo(source_field)
+            .ok_or(Error::from_kind(ErrorKind::DataConfigError("bad transform call".to_string())))?;
+        let datetimes: Vec<NaiveDateTime> = match source_finfo.ty {
+            FieldType::DateTime => orig_ds.get_datetime_field(source_field).unwrap().clone(),
+            FieldType::Date => orig_ds.get_date_field(source_field).unwrap().iter()
+                .map(|d| d.and_hms_opt(0, 0, 0).expect("midnight is valid")).collect(),
+            _ => {
+                return Err(Error::from_kind(ErrorKind::DataConfigError(
+                    "transform: date-part extraction requires date or date-time source values"
+                    .to_string())));
+            }
+        };
+
         let mut tf_data = DataStore::empty();
-        for i in 0..hash_size as usize {
-            tf_data.merge_float(&(target_name.clone() + &format!("_{}", i)[..]),
-                hash_vecs[i].clone())?;
+        for part in self.parts() {
+            if part.requires_time() && source_finfo.ty == FieldType::Date {
+                return Err(Error::from_kind(ErrorKind::DataConfigError(format!(
+                    "transform: date part {:?} requires a date-time source field", part))));
+            }
+            let field_name = target_name.clone() + "_" + part.suffix();
+            match part.field_type() {
+                FieldType::Signed => {
+                    tf_data.merge_signed(&field_name, datetimes.iter()
+                        .map(|dt| i64::from(dt.year())).collect())?;
+                }
+                FieldType::Unsigned => {
+                    tf_data.merge_unsigned(&field_name, datetimes.iter()
+                        .map(|dt| u64::from(date_part_ordinal(dt, part))).collect())?;
+                }
+                FieldType::Boolean => {
+                    tf_data.merge_boolean(&field_name, datetimes.iter()
+                        .map(|dt| dt.weekday() == Weekday::Sat || dt.weekday() == Weekday::Sun)
+                        .collect())?;
+                }
+                _ => {
+                    tf_data.merge_float(&field_name, datetimes.iter()
+                        .map(|dt| date_part_cyclical(dt, part)).collect())?;
+                }
+            }
+            copy_nulls(&mut tf_data, orig_ds, source_field, &field_name);
         }
         Ok(tf_data)
     }
 }
 
+/// Integer value of a date part
+fn date_part_ordinal(dt: &NaiveDateTime, part: DatePart) -> u32 {
+    match part {
+        DatePart::Month     => dt.month(),
+        DatePart::Day       => dt.day(),
+        DatePart::DayOfWeek => dt.weekday().num_days_from_monday(),
+        DatePart::DayOfYear => dt.ordinal(),
+        DatePart::Hour      => dt.hour(),
+        DatePart::Minute    => dt.minute(),
+        _ => unreachable!("date part is not an unsigned integer"),
+    }
+}
+
+/// Sine or cosine encoding of a date part, mapping its period onto a full circle so that the end
+/// of each period is close to the start of the next (e.g. December and January)
+fn date_part_cyclical(dt: &NaiveDateTime, part: DatePart) -> f64 {
+    let (value, period) = match part {
+        DatePart::MonthSin | DatePart::MonthCos => (dt.month0(), 12),
+        DatePart::DayOfWeekSin | DatePart::DayOfWeekCos => {
+            (dt.weekday().num_days_from_monday(), 7)
+        }
+        DatePart::HourSin | DatePart::HourCos => (dt.hour(), 24),
+        _ => unreachable!("date part is not cyclical"),
+    };
+    let angle = 2.0 * f64::consts::PI * f64::from(value) / f64::from(period);
+    match part {
+        DatePart::MonthSin | DatePart::DayOfWeekSin | DatePart::HourSin => angle.sin(),
+        _ => angle.cos(),
+    }
+}
+
+/// Copy the null mask of a source field to a transformed field
+fn copy_nulls(tf_data: &mut DataStore, orig_ds: &DataStore, source_field: &str,
+        target_name: &str) {
+    if let Some(mask) = orig_ds.get_null_mask(source_field) {
+        tf_data.merge_nulls(target_name, mask.clone());
+    }
+}
+
+/// Values of a field which are not missing
+fn present_values(values: &[f64], mask: Option<&Vec<bool>>) -> Vec<f64> {
+    match mask {
+        Some(mask) => values.iter().zip(mask).filter(|&(_, &null)| !null)
+            .map(|(&f, _)| f).collect(),
+        None => values.to_vec(),
+    }
+}
+
 fn mean(v: &Vec<f64>) -> f64 {
     v.iter().fold(0.0, |acc, &f| acc + f) / (v.len() as f64)
 }
@@ -192,25 +451,43 @@ fn stdev(v: &Vec<f64>, mu: f64, correction: f64) -> f64 {
 impl TransformFields for NormalizeConfig {
     fn transform_fields(&self, orig_ds: &DataStore, source_fields: &Vec<String>,
             target_name: &String) -> Result<DataStore> {
-        if !source_fields.len() == 1 {
-            return Err(Error::from_kind(ErrorKind::DataConfigError(
-                "transform: normalization expects only 1 source field".to_string())));
-        }
+        self.apply(orig_ds, source_fields, target_name, &self.fit(orig_ds, source_fields)?)
+    }
 
-        let source_field = source_fields.first().unwrap();
-        let source_finfo = orig_ds.get_fieldinfo(source_field)
-            .ok_or(Error::from_kind(ErrorKind::DataConfigError("bad transform call".to_string())))?;
-        if source_finfo.ty != FieldType::Float {
-            return Err(Error::from_kind(ErrorKind::DataConfigError(
-                "transform: normalize transform requires floats".to_string())));
-        }
+    fn inverse_fields(&self, tf_ds: &DataStore, source_fields: &[String], target_name: &str,
+            params: Option<&FittedParams>) -> Result<DataStore> {
+        let (mean, stdev) = match params {
+            Some(&FittedParams::Normalize { mean, stdev }) => (mean, stdev),
+            _ => { return Err(mismatched_params(target_name)); }
+        };
+        inverse_float(tf_ds, source_fields, target_name, |f| f * stdev + mean)
+    }
+}
 
-        let data_vec = orig_ds.get_float_field(source_field).unwrap();
-        let mean = mean(&data_vec);
-        let stdev = stdev(&data_vec, mean, self.sample_stdev_correction());
+impl FitTransform for NormalizeConfig {
+    fn fit(&self, orig_ds: &DataStore, source_fields: &[String]) -> Result<FittedParams> {
+        let source_field = float_source(orig_ds, source_fields, "normalize")?;
+        let data_vec = orig_ds.get_float_values(source_field).unwrap();
+        let present = present_values(&data_vec, orig_ds.get_null_mask(source_field));
+        let mean = mean(&present);
+        let stdev = stdev(&present, mean, self.sample_stdev_correction());
+        finite_params(source_field, "normalize", &[mean, stdev])?;
+        Ok(FittedParams::Normalize { mean, stdev })
+    }
+
+    fn apply(&self, orig_ds: &DataStore, source_fields: &[String], target_name: &str,
+            params: &FittedParams) -> Result<DataStore> {
+        let source_field = float_source(orig_ds, source_fields, "normalize")?;
+        let (mean, stdev) = match *params {
+            FittedParams::Normalize { mean, stdev } => (mean, stdev),
+            _ => { return Err(mismatched_params(target_name)); }
+        };
+        let target_name = &target_name.to_string();
+        let data_vec = orig_ds.get_float_values(source_field).unwrap();
 
         let mut tf_data = DataStore::empty();
         tf_data.merge_float(target_name, data_vec.iter().map(|&f| (f - mean) / stdev).collect())?;
+        copy_nulls(&mut tf_data, orig_ds, source_field, target_name);
         Ok(tf_data)
     }
 }
@@ -218,22 +495,48 @@ impl TransformFields for NormalizeConfig {
 impl TransformFields for ScaleConfig {
     fn transform_fields(&self, orig_ds: &DataStore, source_fields: &Vec<String>,
             target_name: &String) -> Result<DataStore> {
-        if !source_fields.len() == 1 {
-            return Err(Error::from_kind(ErrorKind::DataConfigError(
-                "transform: scaling expects only 1 source field".to_string())));
-        }
+        self.apply(orig_ds, source_fields, target_name, &self.fit(orig_ds, source_fields)?)
+    }
 
-        let source_field = source_fields.first().unwrap();
-        let source_finfo = orig_ds.get_fieldinfo(source_field)
-            .ok_or(Error::from_kind(ErrorKind::DataConfigError("bad transform call".to_string())))?;
-        if source_finfo.ty != FieldType::Float {
-            return Err(Error::from_kind(ErrorKind::DataConfigError(
-                "transform: scale transform requires floats".to_string())));
+    fn inverse_fields(&self, tf_ds: &DataStore, source_fields: &[String], target_name: &str,
+            params: Option<&FittedParams>) -> Result<DataStore> {
+        let (data_min, data_max) = match params {
+            Some(&FittedParams::Scale { min, max }) => (min, max),
+            _ => { return Err(mismatched_params(target_name)); }
+        };
+        let range = data_max - data_min;
+        if self.has_custom_minmax() {
+            let (min_value, max_value) = (self.min_value(), self.max_value());
+            inverse_float(tf_ds, source_fields, target_name, |f| {
+                let alpha = (f - min_value) / (max_value - min_value);
+                data_min + alpha * range
+            })
+        } else {
+            inverse_float(tf_ds, source_fields, target_name, |f| data_min + f * range)
         }
+    }
+}
+
+impl FitTransform for ScaleConfig {
+    fn fit(&self, orig_ds: &DataStore, source_fields: &[String]) -> Result<FittedParams> {
+        let source_field = float_source(orig_ds, source_fields, "scale")?;
+        let data_vec = orig_ds.get_float_values(source_field).unwrap();
+        let present = present_values(&data_vec, orig_ds.get_null_mask(source_field));
+        let max = present.iter().fold(f64::NEG_INFINITY, |acc, &f| acc.max(f));
+        let min = present.iter().fold(f64::INFINITY, |acc, &f| acc.min(f));
+        finite_params(source_field, "scale", &[min, max])?;
+        Ok(FittedParams::Scale { min, max })
+    }
 
-        let data_vec = orig_ds.get_float_field(source_field).unwrap();
-        let data_max = data_vec.iter().fold(f64::NEG_INFINITY, |acc, &f| acc.max(f));
-        let data_min = data_vec.iter().fold(f64::INFINITY, |acc, &f| acc.min(f));
+    fn apply(&self, orig_ds: &DataStore, source_fields: &[String], target_name: &str,
+            params: &FittedParams) -> Result<DataStore> {
+        let source_field = float_source(orig_ds, source_fields, "scale")?;
+        let (data_min, data_max) = match *params {
+            FittedParams::Scale { min, max } => (min, max),
+            _ => { return Err(mismatched_params(target_name)); }
+        };
+        let target_name = &target_name.to_string();
+        let data_vec = orig_ds.get_float_values(source_field).unwrap();
         let range = data_max - data_min;
 
         let mut tf_data = DataStore::empty();
@@ -246,6 +549,342 @@ impl TransformFields for ScaleConfig {
             tf_data.merge_float(target_name,
                 data_vec.iter().map(|&f| (f - data_min) / range).collect())?;
         }
+        copy_nulls(&mut tf_data, orig_ds, source_field, target_name);
         Ok(tf_data)
     }
 }
+
+impl TransformFields for ExpressionConfig {
+    fn transform_fields(&self, orig_ds: &DataStore, source_fields: &Vec<String>,
+            target_name: &String) -> Result<DataStore> {
+        self.parse(source_fields)?.evaluate(orig_ds, target_name)
+    }
+}
+
+/// Invert a floating-point transformation (normalization or scaling) by applying the specified
+/// function to each value of the target field
+fn inverse_float<F: Fn(f64) -> f64>(tf_ds: &DataStore, source_fields: &[String],
+        target_name: &str, f: F) -> Result<DataStore> {
+    let source_field = inverse_target(source_fields, target_name)?;
+    let values = tf_ds.get_float_values(&target_name.to_string()).ok_or_else(||
+        inverse_missing_field(target_name, FieldType::Float))?;
+
+    let mut inv_data = DataStore::empty();
+    inv_data.merge_float(source_field, values.iter().map(|&value| f(value)).collect())?;
+    copy_nulls(&mut inv_data, tf_ds, target_name, source_field);
+    Ok(inv_data)
+}
+
+/// Source field produced by inverting a transformation, which must have a single source field
+fn inverse_target<'a>(source_fields: &'a [String], target_name: &str) -> Result<&'a String> {
+    if source_fields.len() != 1 {
+        return Err(Error::from_kind(ErrorKind::DataConfigError(format!(
+            "transform: {} is not invertible (expects only 1 source field)", target_name))));
+    }
+    Ok(source_fields.first().unwrap())
+}
+
+/// Error for a field required for inverting a transformation which is missing, or is not of the
+/// expected field type
+fn inverse_missing_field(field_name: &str, ty: FieldType) -> Error {
+    Error::from_kind(ErrorKind::DataFrameError(format!(
+        "transform: inverse requires {:?} field {}", ty, field_name)))
+}
+
+/// Source field of a normalization or scaling, which must be a single floating-point field
+fn float_source<'a>(orig_ds: &DataStore, source_fields: &'a [String], action: &str)
+        -> Result<&'a String> {
+    if source_fields.len() != 1 {
+        return Err(Error::from_kind(ErrorKind::DataConfigError(
+            format!("transform: {} expects only 1 source field", action))));
+    }
+
+    let source_field = source_fields.first().unwrap();
+    let source_finfo = orig_ds.get_fieldinfo(source_field)
+        .ok_or(Error::from_kind(ErrorKind::DataConfigError("bad transform call".to_string())))?;
+    if source_finfo.ty != FieldType::Float {
+        return Err(Error::from_kind(ErrorKind::DataConfigError(
+            format!("transform: {} transform requires floats", action))));
+    }
+    Ok(source_field)
+}
+
+/// Value types which support imputation
+trait Imputable: Clone + Display + PartialOrd {
+    /// Parse a constant value
+    fn parse_constant(value_str: &str) -> Option<Self>;
+    /// Mean of the values, if this type supports averaging
+    fn mean_of(values: &[Self]) -> Option<Self>;
+    /// Midpoint of two values, if this type supports averaging
+    fn midpoint(a: &Self, b: &Self) -> Option<Self>;
+    /// Format a value as a constant (which can be parsed by `parse_constant`)
+    fn format_constant(&self) -> String {
+        self.to_string()
+    }
+}
+impl Imputable for u64 {
+    fn parse_constant(value_str: &str) -> Option<u64> { value_str.parse().ok() }
+    fn mean_of(values: &[u64]) -> Option<u64> {
+        Some((values.iter().fold(0.0, |acc, &u| acc + u as f64) / values.len() as f64).round()
+            as u64)
+    }
+    fn midpoint(a: &u64, b: &u64) -> Option<u64> {
+        Some(((*a as f64 + *b as f64) / 2.0).round() as u64)
+    }
+}
+impl Imputable for i64 {
+    fn parse_constant(value_str: &str) -> Option<i64> { value_str.parse().ok() }
+    fn mean_of(values: &[i64]) -> Option<i64> {
+        Some((values.iter().fold(0.0, |acc, &i| acc + i as f64) / values.len() as f64).round()
+            as i64)
+    }
+    fn midpoint(a: &i64, b: &i64) -> Option<i64> {
+        Some(((*a as f64 + *b as f64) / 2.0).round() as i64)
+    }
+}
+impl Imputable for f64 {
+    fn parse_constant(value_str: &str) -> Option<f64> { value_str.parse().ok() }
+    fn mean_of(values: &[f64]) -> Option<f64> {
+        Some(values.iter().fold(0.0, |acc, &f| acc + f) / values.len() as f64)
+    }
+    fn midpoint(a: &f64, b: &f64) -> Option<f64> {
+        Some((a + b) / 2.0)
+    }
+}
+impl Imputable for String {
+    fn parse_constant(value_str: &str) -> Option<String> { value_str.parse().ok() }
+    fn mean_of(_: &[String]) -> Option<String> { None }
+    fn midpoint(_: &String, _: &String) -> Option<String> { None }
+}
+impl Imputable for bool {
+    fn parse_constant(value_str: &str) -> Option<bool> { value_str.parse().ok() }
+    fn mean_of(_: &[bool]) -> Option<bool> { None }
+    fn midpoint(_: &bool, _: &bool) -> Option<bool> { None }
+}
+// dates, date-times and durations are averaged by their (epoch) seconds
+impl Imputable for NaiveDate {
+    fn parse_constant(value_str: &str) -> Option<NaiveDate> {
+        temporal::parse_date(value_str, None).ok()
+    }
+    fn format_constant(&self) -> String {
+        temporal::format_date(self, None)
+    }
+    fn mean_of(values: &[NaiveDate]) -> Option<NaiveDate> {
+        let seconds: Vec<f64> = values.iter().map(|d| temporal::date_to_epoch(d) as f64)
+            .collect();
+        f64::mean_of(&seconds).and_then(temporal::epoch_to_datetime).map(|dt| dt.date())
+    }
+    fn midpoint(a: &NaiveDate, b: &NaiveDate) -> Option<NaiveDate> {
+        NaiveDate::mean_of(&[*a, *b])
+    }
+}
+impl Imputable for NaiveDateTime {
+    fn parse_constant(value_str: &str) -> Option<NaiveDateTime> {
+        temporal::parse_datetime(value_str, None).ok()
+    }
+    fn format_constant(&self) -> String {
+        temporal::format_datetime(self, None)
+    }
+    fn mean_of(values: &[NaiveDateTime]) -> Option<NaiveDateTime> {
+        let seconds: Vec<f64> = values.iter().map(temporal::datetime_to_epoch).collect();
+        f64::mean_of(&seconds).and_then(temporal::epoch_to_datetime)
+    }
+    fn midpoint(a: &NaiveDateTime, b: &NaiveDateTime) -> Option<NaiveDateTime> {
+        NaiveDateTime::mean_of(&[*a, *b])
+    }
+}
+impl Imputable for Duration {
+    fn parse_constant(value_str: &str) -> Option<Duration> {
+        temporal::parse_duration(value_str).ok()
+    }
+    fn format_constant(&self) -> String {
+        temporal::format_duration(self)
+    }
+    fn mean_of(values: &[Duration]) -> Option<Duration> {
+        let seconds: Vec<f64> = values.iter().map(temporal::duration_to_seconds).collect();
+        f64::mean_of(&seconds).and_then(temporal::duration_from_seconds)
+    }
+    fn midpoint(a: &Duration, b: &Duration) -> Option<Duration> {
+        Duration::mean_of(&[*a, *b])
+    }
+}
+
+impl ImputeConfig {
+    /// Value used to replace the missing values of a field, or `None` for forward-fill
+    /// imputation (where the value depends on the row)
+    fn fill_value<T: Imputable>(&self, values: &[T], nulls: &[bool]) -> Result<Option<T>> {
+        let present: Vec<T> = values.iter().zip(nulls).filter(|&(_, &null)| !null)
+            .map(|(v, _)| v.clone()).collect();
+        if present.is_empty() && self.strategy() != ImputeStrategy::Constant {
+            return Err(Error::from_kind(ErrorKind::DataFrameError(
+                "transform: unable to impute field with no present values".to_string())));
+        }
+        let fill = match self.strategy() {
+            ImputeStrategy::Constant => {
+                let constant = self.constant().ok_or_else(|| Error::from_kind(
+                    ErrorKind::DataConfigError("transform: missing impute value".to_string())))?;
+                T::parse_constant(&constant).ok_or_else(|| Error::from_kind(
+                    ErrorKind::DataConfigError(format!("transform: invalid impute value {}",
+                    constant))))?
+            }
+            ImputeStrategy::Mean => {
+                T::mean_of(&present).ok_or_else(|| Error::from_kind(ErrorKind::DataConfigError(
+                    "transform: mean imputation requires numeric values".to_string())))?
+            }
+            ImputeStrategy::Median => {
+                let mut sorted = present;
+                sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(::std::cmp::Ordering::Equal));
+                let mid = sorted.len() / 2;
+                let median = if sorted.len().is_multiple_of(2) {
+                    T::midpoint(&sorted[mid - 1], &sorted[mid])
+                } else {
+                    // (mean of the single middle value, for numeric types only)
+                    T::mean_of(&sorted[mid..mid + 1])
+                };
+                median.ok_or_else(|| Error::from_kind(ErrorKind::DataConfigError(
+                    "transform: median imputation requires numeric values".to_string())))?
+            }
+            ImputeStrategy::Mode => {
+                // ties are broken by first occurrence
+                let mut counts: HashMap<String, (usize, usize)> = HashMap::new();
+                for (i, value) in present.iter().enumerate() {
+                    counts.entry(value.to_string()).or_insert((0, i)).0 += 1;
+                }
+                let (_, &(_, index)) = counts.iter()
+                    .max_by(|&(_, &(count_a, first_a)), &(_, &(count_b, first_b))| {
+                        count_a.cmp(&count_b).then(first_b.cmp(&first_a))
+                    }).expect("no present values");
+                present[index].clone()
+            }
+            ImputeStrategy::ForwardFill => { return Ok(None); }
+        };
+        Ok(Some(fill))
+    }
+
+    /// Fitted replacement value of a field (in string form)
+    fn fit_values<T: Imputable>(&self, values: &[T], nulls: &[bool]) -> Result<Option<String>> {
+        Ok(self.fill_value(values, nulls)?.map(|fill| fill.format_constant()))
+    }
+
+    /// Impute the missing values of a field with a fitted replacement value (or by forward-filling
+    /// if there is no replacement value)
+    fn impute<T: Imputable>(&self, values: &[T], nulls: &[bool], fill: Option<&str>)
+            -> Result<Vec<T>> {
+        let fill = match fill {
+            Some(fill) => T::parse_constant(fill).ok_or_else(|| Error::from_kind(
+                ErrorKind::DataConfigError(format!("transform: invalid impute value {}",
+                fill))))?,
+            None => {
+                // leading missing values are replaced by the first present value
+                let first = values.iter().zip(nulls).find(|&(_, &null)| !null);
+                let mut last = match first {
+                    Some((value, _)) => value.clone(),
+                    None => {
+                        return Err(Error::from_kind(ErrorKind::DataFrameError(
+                            "transform: unable to impute field with no present values"
+                            .to_string())));
+                    }
+                };
+                return Ok(values.iter().zip(nulls).map(|(value, &null)| {
+                    if !null {
+                        last = value.clone();
+                    }
+                    last.clone()
+                }).collect());
+            }
+        };
+        Ok(values.iter().zip(nulls).map(|(value, &null)| {
+            if null { fill.clone() } else { value.clone() }
+        }).collect())
+    }
+}
+
+impl TransformFields for ImputeConfig {
+    fn transform_fields(&self, orig_ds: &DataStore, source_fields: &Vec<String>,
+            target_name: &String) -> Result<DataStore> {
+        self.apply(orig_ds, source_fields, target_name, &self.fit(orig_ds, source_fields)?)
+    }
+}
+
+impl FitTransform for ImputeConfig {
+    fn fit(&self, orig_ds: &DataStore, source_fields: &[String]) -> Result<FittedParams> {
+        let (source_field, ty) = impute_source(orig_ds, source_fields)?;
+        let nulls = null_mask(orig_ds, source_field);
+        let fill = match ty {
+            FieldType::Unsigned => self.fit_values(
+                orig_ds.get_unsigned_field(source_field).unwrap(), &nulls)?,
+            FieldType::Signed   => self.fit_values(
+                orig_ds.get_signed_field(source_field).unwrap(), &nulls)?,
+            FieldType::Text     => self.fit_values(
+                orig_ds.get_text_field(source_field).unwrap(), &nulls)?,
+            FieldType::Boolean  => self.fit_values(
+                orig_ds.get_boolean_field(source_field).unwrap(), &nulls)?,
+            FieldType::Float    => self.fit_values(
+                &orig_ds.get_float_values(source_field).unwrap(), &nulls)?,
+            FieldType::Date     => self.fit_values(
+                orig_ds.get_date_field(source_field).unwrap(), &nulls)?,
+            FieldType::DateTime => self.fit_values(
+                orig_ds.get_datetime_field(source_field).unwrap(), &nulls)?,
+            FieldType::Duration => self.fit_values(
+                orig_ds.get_duration_field(source_field).unwrap(), &nulls)?,
+        };
+        Ok(FittedParams::Impute { fill })
+    }
+
+    fn apply(&self, orig_ds: &DataStore, source_fields: &[String], target_name: &str,
+            params: &FittedParams) -> Result<DataStore> {
+        let (source_field, ty) = impute_source(orig_ds, source_fields)?;
+        let fill = match *params {
+            FittedParams::Impute { ref fill } => fill.as_ref().map(|fill| &fill[..]),
+            _ => { return Err(mismatched_params(target_name)); }
+        };
+        let target_name = &target_name.to_string();
+        let nulls = null_mask(orig_ds, source_field);
+
+        let mut tf_data = DataStore::empty();
+        match ty {
+            FieldType::Unsigned => tf_data.merge_unsigned(target_name,
+                self.impute(orig_ds.get_unsigned_field(source_field).unwrap(), &nulls, fill)?)?,
+            FieldType::Signed   => tf_data.merge_signed(target_name,
+                self.impute(orig_ds.get_signed_field(source_field).unwrap(), &nulls, fill)?)?,
+            FieldType::Text     => tf_data.merge_text(target_name,
+                self.impute(orig_ds.get_text_field(source_field).unwrap(), &nulls, fill)?)?,
+            FieldType::Boolean  => tf_data.merge_boolean(target_name,
+                self.impute(orig_ds.get_boolean_field(source_field).unwrap(), &nulls, fill)?)?,
+            FieldType::Float    => tf_data.merge_float(target_name,
+                self.impute(&orig_ds.get_float_values(source_field).unwrap(), &nulls, fill)?)?,
+            FieldType::Date     => tf_data.merge_date(target_name,
+                self.impute(orig_ds.get_date_field(source_field).unwrap(), &nulls, fill)?)?,
+            FieldType::DateTime => tf_data.merge_datetime(target_name,
+                self.impute(orig_ds.get_datetime_field(source_field).unwrap(), &nulls, fill)?)?,
+            FieldType::Duration => tf_data.merge_duration(target_name,
+                self.impute(orig_ds.get_duration_field(source_field).unwrap(), &nulls, fill)?)?,
+        }
+        if self.add_indicator() {
+            tf_data.merge_boolean(&(target_name.clone() + "_was_missing"), nulls)?;
+        }
+        Ok(tf_data)
+    }
+}
+
+/// Source field (and its field type) of an imputation, which must be a single field
+fn impute_source<'a>(orig_ds: &DataStore, source_fields: &'a [String])
+        -> Result<(&'a String, FieldType)> {
+    if source_fields.len() != 1 {
+        return Err(Error::from_kind(ErrorKind::DataConfigError(
+            "transform: imputation expects only 1 source field".to_string())));
+    }
+
+    let source_field = source_fields.first().unwrap();
+    let source_finfo = orig_ds.get_fieldinfo(source_field)
+        .ok_or(Error::from_kind(ErrorKind::DataConfigError("bad transform call".to_string())))?;
+    Ok((source_field, source_finfo.ty))
+}
+
+/// Null mask of a field, with all values present if the field has no mask
+fn null_mask(orig_ds: &DataStore, field_name: &str) -> Vec<bool> {
+    match orig_ds.get_null_mask(field_name) {
+        Some(mask) => mask.clone(),
+        None       => vec![false; orig_ds.nrows()],
+    }
+}
@@ -9,6 +9,20 @@ extern crate serde_json;
 extern crate csv;
 extern crate encoding;
 extern crate toml;
+extern crate flate2;
+extern crate zstd;
+extern crate bzip2;
+extern crate calamine;
+extern crate glob;
+extern crate chrono;
+extern crate arrow_array;
+extern crate arrow_cast;
+extern crate arrow_ipc;
+extern crate arrow_schema;
+extern crate bytes;
+extern crate parquet;
+extern crate rusqlite;
+extern crate regex;
 #[macro_use] extern crate error_chain;
 
 extern crate wee_matrix as matrix;
@@ -0,0 +1,64 @@
+extern crate etl;
+extern crate toml;
+
+mod common;
+
+use common::frame;
+
+const CONFIG_AB: &str = r#"
+[[source_files]]
+name = "source"
+fields = [ { source_name = "a", field_type = "Signed" },
+           { source_name = "b", field_type = "Text" } ]
+"#;
+
+const CONFIG_AC: &str = r#"
+[[source_files]]
+name = "source"
+fields = [ { source_name = "a", field_type = "Signed" },
+           { source_name = "c", field_type = "Float" } ]
+"#;
+
+const CONFIG_A_TEXT: &str = r#"
+[[source_files]]
+name = "source"
+fields = [ { source_name = "a", field_type = "Text" },
+           { source_name = "b", field_type = "Text" } ]
+"#;
+
+#[test]
+fn test_append() {
+    let mut df = frame(CONFIG_AB, "a,b\n1,x\n2,y\n");
+    df.append(frame(CONFIG_AB, "b,a\nz,3\n")).unwrap();
+
+    assert_eq!(df.nrows(), 3);
+    assert_eq!(df.get_signed_field("a").unwrap(), &[1, 2, 3]);
+    assert_eq!(df.get_text_field("b").unwrap(), &["x", "y", "z"]);
+}
+
+#[test]
+fn test_append_mismatch() {
+    let mut df = frame(CONFIG_AB, "a,b\n1,x\n2,y\n");
+    assert!(df.append(frame(CONFIG_AC, "a,c\n3,1.5\n")).is_err());
+
+    let mut df = frame(CONFIG_AB, "a,b\n1,x\n2,y\n");
+    assert!(df.append(frame(CONFIG_A_TEXT, "a,b\n3,z\n")).is_err());
+    assert!(df.append_fill_missing(frame(CONFIG_A_TEXT, "a,b\n3,z\n")).is_err());
+}
+
+#[test]
+fn test_append_fill_missing() {
+    let mut df = frame(CONFIG_AB, "a,b\n1,x\n2,y\n");
+    df.append_fill_missing(frame(CONFIG_AC, "a,c\n3,1.5\n")).unwrap();
+
+    let mut fieldnames = df.fieldnames();
+    fieldnames.sort();
+    assert_eq!(fieldnames, ["a", "b", "c"]);
+
+    assert_eq!(df.nrows(), 3);
+    assert_eq!(df.get_signed_field("a").unwrap(), &[1, 2, 3]);
+    assert_eq!(df.get_text_field("b").unwrap(), &["x", "y", ""]);
+    let c = df.get_float_field("c").unwrap();
+    assert!(c[0].is_nan() && c[1].is_nan());
+    assert_eq!(c[2], 1.5);
+}
@@ -0,0 +1,71 @@
+extern crate etl;
+extern crate toml;
+
+use std::env;
+use std::fs;
+use std::process::{Command, Output};
+
+use etl::dataframe::DataConfig;
+
+fn etl(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_etl")).args(args).output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+const EXPECTED_CSV: &str = "\
+id,name,balance,joined,session,notes
+1,alice,10.5,2017-01-15,01:30:00,
+2,bob,,2017-02-01,00:45:10,
+3,carol,7,,00:05:00,called twice
+4,dave,2.25,2017-03-20,02:00:00,
+";
+
+#[test]
+fn test_cli_run() {
+    let output = etl(&["run", "tests/data/cli_test.toml"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), EXPECTED_CSV);
+
+    let path = env::temp_dir().join("etl_cli_test_run.csv");
+    let output = etl(&["run", "tests/data/cli_test.toml", "--output", path.to_str().unwrap()]);
+    assert!(output.status.success());
+    assert_eq!(fs::read_to_string(&path).unwrap(), EXPECTED_CSV);
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_cli_validate() {
+    let output = etl(&["validate", "tests/data/cli_test.toml"]);
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("valid (1 source file(s), 0 transform(s))"));
+
+    let output = etl(&["validate", "tests/data/no_such_config.toml"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8(output.stderr).unwrap().starts_with("Error: "));
+}
+
+#[test]
+fn test_cli_infer() {
+    let output = etl(&["infer", "tests/data/infer_test.csv"]);
+    assert!(output.status.success());
+    let config: DataConfig = toml::from_str(&stdout(&output)).unwrap();
+    assert_eq!(config.source_files[0].fields.len(), 9);
+
+    assert!(!etl(&["infer", "tests/data/infer_test.csv", "--delimiter", "ab"]).status.success());
+}
+
+#[test]
+fn test_cli_describe() {
+    let output = etl(&["describe", "tests/data/cli_test.toml"]);
+    assert!(output.status.success());
+    let description = stdout(&output);
+    let lines: Vec<&str> = description.lines().collect();
+    assert_eq!(lines[0], "4 rows, 6 fields");
+    assert!(lines[1].starts_with("field"));
+    assert!(lines[4].starts_with("balance") && lines[4].ends_with("1  min 2.25, max 10.5"));
+    assert!(lines[5].starts_with("joined") && lines[5].ends_with("min 2017-01-15, max 2017-03-20"));
+    assert!(lines[7].starts_with("notes") && lines[7].ends_with("3  1 distinct"));
+}
@@ -0,0 +1,122 @@
+extern crate arrow_ipc;
+extern crate arrow_schema;
+extern crate etl;
+extern crate toml;
+
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use arrow_ipc::reader::FileReader;
+use arrow_schema::{DataType, TimeUnit};
+
+use etl::dataframe::{DataConfig, DataFrame, SourceFormat};
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(file!()).parent().unwrap().join("data").join(name)
+}
+
+fn frame() -> DataFrame {
+    DataFrame::load(&fixture("roundtrip_test.toml")).unwrap().1
+}
+
+/// Configuration for reading the fields of the round-trip test frame back from a written file
+fn written_config(name: &str, format: SourceFormat) -> DataConfig {
+    let mut config: DataConfig = toml::from_str(
+        &fs::read_to_string(fixture("roundtrip_written.toml")).unwrap()).unwrap();
+    config.source_files[0].name = name.to_string();
+    config.source_files[0].format = Some(format);
+    config
+}
+
+fn reload(format: SourceFormat, written: Vec<u8>) -> DataFrame {
+    let mut sources = HashMap::new();
+    sources.insert("written".to_string(), Cursor::new(written));
+    DataFrame::from_config(&written_config("written", format), sources).unwrap()
+}
+
+fn assert_same(df: &DataFrame, reloaded: &DataFrame) {
+    assert_eq!(reloaded.nrows(), 3);
+    assert_eq!(reloaded.get_unsigned_field("id"), df.get_unsigned_field("id"));
+    assert_eq!(reloaded.get_signed_field("delta"), df.get_signed_field("delta"));
+    assert_eq!(reloaded.get_text_field("name"), df.get_text_field("name"));
+    assert_eq!(reloaded.get_boolean_field("flag"), df.get_boolean_field("flag"));
+    // missing floating-point values are NaN placeholders, so only compare present values
+    let score = reloaded.get_float_field("score").unwrap();
+    assert_eq!((score[0], score[2]), (1.5, 3.0));
+    assert_eq!(reloaded.get_date_field("day"), df.get_date_field("day"));
+    assert_eq!(reloaded.get_datetime_field("at"), df.get_datetime_field("at"));
+    assert_eq!(reloaded.get_duration_field("took"), df.get_duration_field("took"));
+    assert_eq!(reloaded.get_float_field("name_cy"), df.get_float_field("name_cy"));
+    for field_name in df.fieldnames() {
+        assert_eq!(reloaded.get_null_mask(field_name), df.get_null_mask(field_name));
+    }
+}
+
+#[test]
+fn test_parquet_roundtrip() {
+    let df = frame();
+    let mut written = vec![];
+    df.write_parquet(&mut written).unwrap();
+    assert_eq!(&written[..4], b"PAR1");
+    assert_same(&df, &reload(SourceFormat::Parquet, written));
+}
+
+#[test]
+fn test_arrow_ipc_roundtrip() {
+    let df = frame();
+    let mut written = vec![];
+    df.write_arrow_ipc(&mut written).unwrap();
+    assert_same(&df, &reload(SourceFormat::ArrowIpc, written));
+}
+
+#[test]
+fn test_arrow_schema() {
+    let df = frame();
+    let mut written = vec![];
+    df.write_arrow_ipc(&mut written).unwrap();
+    let reader = FileReader::try_new(Cursor::new(written), None).unwrap();
+    let schema = reader.schema();
+
+    // fields keep their order, including generated transform fields
+    let fields: Vec<(&str, &DataType)> = schema.fields().iter()
+        .map(|field| (&field.name()[..], field.data_type())).collect();
+    assert_eq!(fields, [("id", &DataType::UInt64), ("delta", &DataType::Int64),
+        ("name", &DataType::Utf8), ("flag", &DataType::Boolean), ("score", &DataType::Float64),
+        ("day", &DataType::Date32), ("at", &DataType::Timestamp(TimeUnit::Microsecond, None)),
+        ("took", &DataType::Duration(TimeUnit::Microsecond)), ("name_al", &DataType::Float64),
+        ("name_cy", &DataType::Float64)]);
+
+    // missing columns are reported
+    let config: DataConfig = toml::from_str(r#"
+[[source_files]]
+name = "written"
+format = "ArrowIpc"
+fields = [ { source_name = "no_such_column", field_type = "Text" } ]
+"#).unwrap();
+    let mut written = vec![];
+    df.write_arrow_ipc(&mut written).unwrap();
+    let mut sources = HashMap::new();
+    sources.insert("written".to_string(), Cursor::new(written));
+    assert!(DataFrame::from_config(&config, sources).is_err());
+}
+
+#[test]
+fn test_columnar_output() {
+    let dir = env::temp_dir().join("etl_columnar_test");
+    fs::create_dir_all(&dir).unwrap();
+    fs::copy(fixture("roundtrip_test.csv"), dir.join("roundtrip_test.csv")).unwrap();
+    fs::write(dir.join("config.toml"), fs::read_to_string(fixture("roundtrip_test.toml")).unwrap()
+        + "\n[output]\nname = \"out.parquet\"\nformat = \"Parquet\"\n").unwrap();
+
+    let (config, df) = DataFrame::load(&dir.join("config.toml")).unwrap();
+    df.write_output(config.output.as_ref().unwrap()).unwrap();
+
+    fs::write(dir.join("reload.toml"),
+        toml::to_string(&written_config("out.parquet", SourceFormat::Parquet)).unwrap()).unwrap();
+    let (_, reloaded) = DataFrame::load(&dir.join("reload.toml")).unwrap();
+    assert_same(&df, &reloaded);
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,34 @@
+//! Helpers shared by the integration tests, for loading dataframes from inline configurations and
+//! data
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+
+use toml;
+
+use etl::dataframe::{DataConfig, DataFrame};
+
+/// Name of the single source file of inline test configurations
+pub const SOURCE: &str = "source";
+
+/// Parse an inline TOML configuration
+pub fn config(config: &str) -> DataConfig {
+    toml::from_str(config).unwrap()
+}
+
+/// Sources for an inline configuration, reading the source file from a string
+pub fn sources(data: &str) -> HashMap<String, &[u8]> {
+    let mut sources = HashMap::new();
+    sources.insert(SOURCE.to_string(), data.as_bytes());
+    sources
+}
+
+/// Load a dataframe from an inline configuration and the data of its source file
+pub fn load(config_str: &str, data: &str) -> Result<DataFrame, String> {
+    DataFrame::from_config(&config(config_str), sources(data)).map_err(|e| e.to_string())
+}
+
+/// Load a dataframe which is expected to be valid from an inline configuration and data
+pub fn frame(config: &str, data: &str) -> DataFrame {
+    load(config, data).unwrap()
+}
@@ -0,0 +1,37 @@
+extern crate etl;
+
+use std::path::PathBuf;
+use etl::dataframe::DataFrame;
+
+#[test]
+fn test_compression() {
+    let data_path = PathBuf::from(file!()).parent().unwrap().join("data/compression_test.toml");
+
+    let (config, df) = DataFrame::load(data_path.as_path()).unwrap();
+
+    println!("{:?}", config);
+    println!("{:?}", df);
+
+    let mut fieldnames = df.fieldnames();
+    fieldnames.sort();
+    assert_eq!(fieldnames, ["c_bzip2", "c_gzip", "c_zstd", "e_detected", "f"]);
+    assert_eq!(df.nrows(), 9);
+
+    let expected_c = [1, 2, 3, 4, 5, 6, 7, 8, 9];
+    assert_eq!(df.get_signed_field("c_gzip").unwrap(), &expected_c);
+    assert_eq!(df.get_signed_field("c_zstd").unwrap(), &expected_c);
+    assert_eq!(df.get_signed_field("c_bzip2").unwrap(), &expected_c);
+
+    assert_eq!(df.get_text_field("e_detected").unwrap(),
+        &["M", "e2", "F", "M", "M", "F", "F", "F", "F"]);
+    assert_eq!(df.get_float_field("f").unwrap(),
+        &[5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 11.0, 12.0, 15.0]);
+}
+
+#[test]
+fn test_unsupported_compression() {
+    let data_path = PathBuf::from(file!()).parent().unwrap()
+        .join("data/compression_test_bad.toml");
+
+    assert!(DataFrame::load(data_path.as_path()).is_err());
+}
@@ -0,0 +1,114 @@
+extern crate etl;
+extern crate toml;
+
+mod common;
+
+use etl::dataframe::{DataFrame, FieldType};
+
+const SOURCE: &str = r#"
+[[source_files]]
+name = "source"
+fields = [ { source_name = "income", field_type = "Float" },
+           { source_name = "household income", field_type = "Float" },
+           { source_name = "age", field_type = "Unsigned" },
+           { source_name = "gender_code", field_type = "Text", null_values = [""] },
+           { source_name = "member", field_type = "Boolean" },
+           { source_name = "joined", field_type = "Date" } ]
+"#;
+
+const DATA: &str = "\
+income,household income,age,gender_code,member,joined
+50,100,0,M,true,2020-01-01
+30,120,9,F,false,2021-06-30
+,80,99,,true,2019-03-15
+";
+
+fn expression(expression: &str, source_fields: &[&str]) -> String {
+    format!("\n[[transforms]]\nsource_fields = {:?}\ntarget_name = \"result\"\n\
+        method = {{ action = \"Expression\", expression = {:?} }}\n", source_fields, expression)
+}
+
+fn load(transforms: &str) -> Result<DataFrame, String> {
+    common::load(&(SOURCE.to_string() + transforms), DATA)
+}
+
+fn eval(expr: &str, source_fields: &[&str]) -> DataFrame {
+    load(&expression(expr, source_fields)).unwrap()
+}
+
+#[test]
+fn test_arithmetic() {
+    let df = eval("income / `household income`", &["income", "household income"]);
+    assert_eq!(df.get_field_type("result"), Some(FieldType::Float));
+    assert_eq!(df.get_float_field("result").unwrap()[..2], [0.5, 0.25]);
+    // missing operands give missing results
+    assert_eq!(df.get_null_mask("result").unwrap(), &vec![false, false, true]);
+
+    // integer arithmetic stays integral (and signed), with the usual precedence
+    let df = eval("2 * age - 10 % 4 + -1", &["age"]);
+    assert_eq!(df.get_signed_field("result").unwrap(), &vec![-3, 15, 195]);
+
+    let df = eval("log(age + 1)", &["age"]);
+    let values = df.get_float_field("result").unwrap();
+    assert_eq!(values[0], 0.0);
+    assert!((values[1] - 10f64.ln()).abs() < 1e-12);
+
+    let df = eval("max(age, 5) + round(pow(2, 0.5) * 10) / 10", &["age"]);
+    assert_eq!(df.get_float_field("result").unwrap(), &vec![6.4, 10.4, 100.4]);
+}
+
+#[test]
+fn test_conditionals() {
+    let df = eval("if(gender_code == \"M\", 0, 1)", &["gender_code"]);
+    assert_eq!(df.get_signed_field("result").unwrap()[..2], [0, 1]);
+    assert_eq!(df.null_count("result"), 1);
+
+    let df = eval("if(is_null(gender_code), \"unknown\", lower(gender_code))", &["gender_code"]);
+    assert_eq!(df.get_text_field("result").unwrap(), &vec!["m", "f", "unknown"]);
+    assert_eq!(df.null_count("result"), 0);
+
+    let df = eval("member && (age >= 9 || !(income < 40))", &["member", "age", "income"]);
+    assert_eq!(df.get_boolean_field("result").unwrap()[..2], [true, false]);
+    assert_eq!(df.get_null_mask("result").unwrap(), &vec![false, false, true]);
+
+    let df = eval("coalesce(income, `household income` / 2)", &["income", "household income"]);
+    assert_eq!(df.get_float_field("result").unwrap(), &vec![50.0, 30.0, 40.0]);
+    assert_eq!(df.null_count("result"), 0);
+
+    let df = eval("concat(upper(gender_code), \"-\", age) + '!'", &["gender_code", "age"]);
+    assert_eq!(df.get_text_field("result").unwrap()[..2], ["M-0!", "F-9!"]);
+}
+
+#[test]
+fn test_expression_errors() {
+    let check = |expr: &str, source_fields: &[&str]| {
+        common::config(&(SOURCE.to_string() + &expression(expr, source_fields))).validate()
+            .is_ok()
+    };
+    // syntax errors and fields missing from the source fields are found when validating
+    assert!(check("income * 2", &["income"]));
+    assert!(!check("income *", &["income"]));
+    assert!(!check("(income", &["income"]));
+    assert!(!check("income 2", &["income"]));
+    assert!(!check("frobnicate(income)", &["income"]));
+    assert!(!check("pow(income)", &["income"]));
+    assert!(!check("\"unterminated", &[]));
+    assert!(!check("income * age", &["income"]));
+
+    // type errors are found when transforming
+    assert!(load(&expression("gender_code * 2", &["gender_code"])).is_err());
+    assert!(load(&expression("if(age, 1, 2)", &["age"])).is_err());
+    assert!(load(&expression("if(member, 1, \"2\")", &["member"])).is_err());
+    assert!(load(&expression("age == \"9\"", &["age"])).is_err());
+    assert!(load(&expression("joined + 1", &["joined"])).is_err());
+}
+
+#[test]
+fn test_unsigned_out_of_range() {
+    let df = common::frame(&("[[source_files]]\nname = \"source\"\n\
+        fields = [ { source_name = \"big\", field_type = \"Unsigned\" } ]\n".to_string()
+        + &expression("big + 1", &["big"])), "big\n1\n18446744073709551615\n");
+    // unsigned values too large for signed integers are missing (rather than wrapping)
+    assert_eq!(df.get_signed_field("result").unwrap()[0], 2);
+    assert_eq!(df.get_null_mask("result").unwrap(), &vec![false, true]);
+}
@@ -1,7 +1,9 @@
 extern crate etl;
+extern crate toml;
 
+use std::collections::HashMap;
 use std::path::PathBuf;
-use etl::dataframe::DataFrame;
+use etl::dataframe::{DataConfig, DataFrame};
 
 #[test]
 fn test_filter() {
@@ -26,3 +28,114 @@ fn test_filter() {
     assert!(field_f.is_some());
     assert_eq!(field_f.unwrap(), &[7.0, 10.0]);
 }
+
+const COMBINATOR_SOURCE: &str = r#"
+[[source_files]]
+name = "source"
+fields = [ { source_name = "country", field_type = "Text", null_values = [""] },
+           { source_name = "revenue", field_type = "Float" } ]
+"#;
+
+const COMBINATOR_DATA: &str = "\
+country,revenue
+US,10
+CA,0
+MX,5
+CA,3.5
+,8
+US,-2
+";
+
+fn load_filtered(filters: &str) -> Result<DataFrame, String> {
+    let config: DataConfig = toml::from_str(&(COMBINATOR_SOURCE.to_string() + filters)).unwrap();
+    let mut sources = HashMap::new();
+    sources.insert("source".to_string(), COMBINATOR_DATA.as_bytes());
+    DataFrame::from_config(&config, sources).map_err(|e| e.to_string())
+}
+
+#[test]
+fn test_filter_combinators() {
+    // country is US or CA, and revenue > 0
+    let df = load_filtered(r#"
+[[source_files.filters]]
+source_field = "country"
+filter = { method = "Or", filters = [ { method = "Match", text = "US" }, { method = "Match", text = "CA" } ] }
+
+[[source_files.filters]]
+source_field = "revenue"
+filter = { method = "Inequality", inequality = "Gt", float = 0.0 }
+"#).unwrap();
+    assert_eq!(df.get_text_field("country").unwrap(), &["US", "CA"]);
+    assert_eq!(df.get_float_field("revenue").unwrap(), &[10.0, 3.5]);
+
+    // multiple filters on the same field must all pass
+    let df = load_filtered(r#"
+[[source_files.filters]]
+source_field = "revenue"
+filter = { method = "Inequality", inequality = "Gt", float = 0.0 }
+
+[[source_files.filters]]
+source_field = "revenue"
+filter = { method = "Inequality", inequality = "Lt", float = 9.0 }
+"#).unwrap();
+    assert_eq!(df.get_float_field("revenue").unwrap(), &[5.0, 3.5, 8.0]);
+
+    // negation (which keeps missing values) and nested combinators
+    let df = load_filtered(r#"
+[[source_files.filters]]
+source_field = "country"
+filter = { method = "Not", filter = { method = "Or", filters = [ { method = "Match", text = "US" }, { method = "And", filters = [ { method = "NotNull" }, { method = "MatchNot", text = "CA" } ] } ] } }
+"#).unwrap();
+    assert_eq!(df.get_text_field("country").unwrap(), &["CA", "CA", ""]);
+    assert_eq!(df.null_count("country"), 1);
+
+    // combinators require nested filters
+    assert!(load_filtered(r#"
+[[source_files.filters]]
+source_field = "country"
+filter = { method = "Not", filter = { method = "And", filters = [] } }
+"#).is_err());
+}
+
+#[test]
+fn test_filter_text_and_sets() {
+    let countries = |filter: &str| {
+        let df = load_filtered(&format!("\n[[source_files.filters]]\nsource_field = \"country\"\n\
+            filter = {}\n", filter)).unwrap();
+        df.get_text_field("country").unwrap().clone()
+    };
+    assert_eq!(countries(r#"{ method = "Regex", pattern = "^(US|MX)$" }"#), &["US", "MX", "US"]);
+    assert_eq!(countries(r#"{ method = "Regex", pattern = "A" }"#), &["CA", "CA"]);
+    assert_eq!(countries(r#"{ method = "StartsWith", text = "M" }"#), &["MX"]);
+    assert_eq!(countries(r#"{ method = "EndsWith", text = "S" }"#), &["US", "US"]);
+    assert_eq!(countries(r#"{ method = "Contains", text = "X" }"#), &["MX"]);
+    assert_eq!(countries(r#"{ method = "In", values = ["CA", "MX"] }"#), &["CA", "MX", "CA"]);
+
+    let revenues = |filter: &str| {
+        let df = load_filtered(&format!("\n[[source_files.filters]]\nsource_field = \"revenue\"\n\
+            filter = {}\n", filter)).unwrap();
+        df.get_float_field("revenue").unwrap().clone()
+    };
+    assert_eq!(revenues(r#"{ method = "In", values = [0.0, 3.5, 10.0] }"#), &[10.0, 0.0, 3.5]);
+    // integers match integral floating-point values, and other values don't match
+    assert_eq!(revenues(r#"{ method = "In", values = [0, 10] }"#), &[10.0, 0.0]);
+    // ranges include both ends
+    assert_eq!(revenues(r#"{ method = "Between", float = [0.0, 5.0] }"#), &[0.0, 5.0, 3.5]);
+
+    // regular expressions are compiled when the configuration is validated
+    let invalid = |filter: &str| {
+        let config: DataConfig = toml::from_str(&format!("{}\n[[source_files.filters]]\n\
+            source_field = \"country\"\nfilter = {}\n", COMBINATOR_SOURCE, filter)).unwrap();
+        config.validate().is_err()
+    };
+    assert!(invalid(r#"{ method = "Regex", pattern = "(US" }"#));
+    assert!(invalid(r#"{ method = "Not", filter = { method = "Regex", pattern = "[" } }"#));
+    assert!(invalid(r#"{ method = "In", values = [] }"#));
+    // set values must be comparable with the values of the field
+    assert!(invalid(r#"{ method = "In", values = [1, 2] }"#));
+    assert!(invalid(r#"{ method = "Or", filters = [ { method = "In", values = [true] } ] }"#));
+    assert!(!invalid(r#"{ method = "In", values = ["1", "2"] }"#));
+    assert!(invalid(r#"{ method = "Between" }"#));
+    assert!(invalid(r#"{ method = "Between", date = ["2017-01-01", "soon"] }"#));
+    assert!(!invalid(r#"{ method = "Regex", pattern = "^[A-Z]{2}$" }"#));
+}
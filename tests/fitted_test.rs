@@ -0,0 +1,145 @@
+extern crate etl;
+extern crate toml;
+
+mod common;
+
+use std::env;
+use std::fs;
+
+use etl::dataframe::{DataFrame, FittedParams, FittedPipeline};
+
+use common::sources;
+
+const CONFIG: &str = r#"
+[[source_files]]
+name = "source"
+fields = [ { source_name = "color", field_type = "Text", null_values = [""] },
+           { source_name = "size", field_type = "Float" },
+           { source_name = "rooms", field_type = "Unsigned" } ]
+
+[[transforms]]
+source_fields = ["color"]
+target_name = "color"
+method = { action = "VectorizeOneHot" }
+
+[[transforms]]
+source_fields = ["size"]
+target_name = "size_norm"
+method = { action = "Normalize", sample_stdev_correction = 1.0 }
+
+[[transforms]]
+source_fields = ["size"]
+target_name = "size_scaled"
+method = { action = "Scale" }
+
+[[transforms]]
+source_fields = ["rooms"]
+target_name = "rooms_filled"
+method = { action = "Impute", strategy = "Median" }
+
+[[transforms]]
+source_fields = ["color"]
+target_name = "color_upper"
+method = { action = "Map", default_value = "OTHER", map = { red = "RED", blue = "BLUE" } }
+"#;
+
+const TRAIN: &str = "\
+color,size,rooms
+red,1,1
+blue,3,
+red,5,3
+";
+
+const TEST: &str = "\
+color,size,rooms
+green,3,
+red,7,5
+";
+
+#[test]
+fn test_fit_apply() {
+    let config = common::config(CONFIG);
+    let (train, fitted) = DataFrame::fit_from_config(&config, sources(TRAIN)).unwrap();
+    // only stateful transforms are fitted
+    assert_eq!(fitted.transforms.iter().map(|t| &t.target_name[..]).collect::<Vec<_>>(),
+        ["color", "size_norm", "size_scaled", "rooms_filled"]);
+    assert_eq!(fitted.params("size_scaled"), Some(&FittedParams::Scale { min: 1.0, max: 5.0 }));
+    assert_eq!(fitted.params("color"), Some(&FittedParams::VectorizeOneHot {
+        categories: vec!["red".to_string(), "blue".to_string()] }));
+    assert_eq!(fitted.params("rooms_filled"),
+        Some(&FittedParams::Impute { fill: Some("2".to_string()) }));
+    assert_eq!(fitted.params("color_upper"), None);
+    assert_eq!(train.get_float_field("size_norm").unwrap(), &vec![-1.0, 0.0, 1.0]);
+
+    let test = DataFrame::from_config_fitted(&config, sources(TEST), &fitted).unwrap();
+    // normalization and scaling use the parameters of the training data
+    assert_eq!(test.get_float_field("size_norm").unwrap(), &vec![0.0, 2.0]);
+    assert_eq!(test.get_float_field("size_scaled").unwrap(), &vec![0.5, 1.5]);
+    // one-hot fields match the training data, and unknown categories are encoded as all off
+    assert_eq!(test.get_float_field("color_red").unwrap(), &vec![0.0, 1.0]);
+    assert_eq!(test.get_float_field("color_blue").unwrap(), &vec![0.0, 0.0]);
+    assert!(test.get_float_field("color_green").is_none());
+    // imputation uses the training median
+    assert_eq!(test.get_unsigned_field("rooms_filled").unwrap(), &vec![2, 5]);
+    assert_eq!(test.get_text_field("color_upper").unwrap(), &vec!["OTHER", "RED"]);
+
+    // without a fitted pipeline, parameters are learned from the test data
+    let refit = DataFrame::from_config(&config, sources(TEST)).unwrap();
+    assert_eq!(refit.get_float_field("color_green").unwrap(), &vec![1.0, 0.0]);
+    assert_eq!(refit.get_unsigned_field("rooms_filled").unwrap(), &vec![5, 5]);
+
+    // every stateful transform requires fitted parameters
+    let mut partial = fitted.clone();
+    partial.transforms.retain(|t| t.target_name != "size_norm");
+    assert!(DataFrame::from_config_fitted(&config, sources(TEST), &partial).is_err());
+    let mut mismatched = fitted.clone();
+    mismatched.transforms[1].params = FittedParams::Scale { min: 0.0, max: 1.0 };
+    assert!(DataFrame::from_config_fitted(&config, sources(TEST), &mismatched).is_err());
+}
+
+#[test]
+fn test_fitted_files() {
+    let dir = env::temp_dir().join("etl_fitted_test");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("train.csv"), TRAIN).unwrap();
+    fs::write(dir.join("test.csv"), TEST).unwrap();
+    fs::write(dir.join("train.toml"), CONFIG.replace("\"source\"", "\"train.csv\"")).unwrap();
+    fs::write(dir.join("test.toml"), CONFIG.replace("\"source\"", "\"test.csv\"")).unwrap();
+
+    let (_, _, fitted) = DataFrame::fit(&dir.join("train.toml")).unwrap();
+    // fitted pipelines are saved as TOML or JSON, depending on the file extension
+    for filename in &["fitted.toml", "fitted.json"] {
+        fitted.save(&dir.join(filename)).unwrap();
+        let loaded = FittedPipeline::load(&dir.join(filename)).unwrap();
+        assert_eq!(loaded, fitted);
+
+        let (_, test) = DataFrame::load_fitted(&dir.join("test.toml"), &loaded).unwrap();
+        assert_eq!(test.get_float_field("size_scaled").unwrap(), &vec![0.5, 1.5]);
+        assert_eq!(test.get_unsigned_field("rooms_filled").unwrap(), &vec![2, 5]);
+    }
+    assert!(fs::read_to_string(dir.join("fitted.json")).unwrap().starts_with('{'));
+    assert!(FittedPipeline::load(&dir.join("missing.toml")).is_err());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_fit_missing_values() {
+    let config = common::config(CONFIG);
+    let dir = env::temp_dir().join("etl_fitted_missing_test");
+    fs::create_dir_all(&dir).unwrap();
+    // normalization and scaling can't be fitted to fields without any present values, since
+    // their parameters would be non-finite and couldn't be saved and reloaded
+    let missing = "color,size,rooms\nred,,1\nblue,,2\n";
+    assert!(DataFrame::fit_from_config(&config, sources(missing)).is_err());
+
+    let (_, fitted) = DataFrame::fit_from_config(&config, sources(TRAIN)).unwrap();
+    for filename in &["fitted.toml", "fitted.json"] {
+        fitted.save(&dir.join(filename)).unwrap();
+        assert_eq!(FittedPipeline::load(&dir.join(filename)).unwrap(), fitted);
+    }
+    // parameters saved as JSON nulls are rejected when loading
+    fs::write(dir.join("null.json"), r#"{ "transforms": [ { "target_name": "size_scaled",
+        "params": { "method": "Scale", "min": null, "max": null } } ] }"#).unwrap();
+    assert!(FittedPipeline::load(&dir.join("null.json")).is_err());
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,24 @@
+extern crate etl;
+
+use std::path::PathBuf;
+use etl::dataframe::DataFrame;
+
+#[test]
+fn test_fixed_width() {
+    let data_path = PathBuf::from(file!()).parent().unwrap().join("data/fixed_width_test.toml");
+
+    let (config, df) = DataFrame::load(data_path.as_path()).unwrap();
+
+    println!("{:?}", config);
+    println!("{:?}", df);
+
+    let mut fieldnames = df.fieldnames();
+    fieldnames.sort();
+    assert_eq!(fieldnames, ["age", "id", "income", "name"]);
+
+    assert_eq!(df.nrows(), 2);
+    assert_eq!(df.get_text_field("id").unwrap(), &["000001", "000003"]);
+    assert_eq!(df.get_text_field("name").unwrap(), &["Alice", "Carol"]);
+    assert_eq!(df.get_unsigned_field("age").unwrap(), &[34, 29]);
+    assert_eq!(df.get_float_field("income").unwrap(), &[52000.5, 28639.25]);
+}
@@ -0,0 +1,75 @@
+extern crate etl;
+extern crate toml;
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::PathBuf;
+
+use etl::dataframe::{DataConfig, DataFrame};
+
+const CONFIG: &str = r#"
+[[source_files]]
+name = "people"
+delimiter = ","
+fields = [ { source_name = "name", field_type = "Text" },
+           { source_name = "age", field_type = "Unsigned" } ]
+
+[[source_files]]
+name = "transform_test2.csv.gz"
+delimiter = "\t"
+fields = [ { source_name = "f", field_type = "Float" } ]
+
+[[transforms]]
+source_fields = [ "name" ]
+target_name = "name_length_map"
+method = { action = "Map", default_value = "long", map = { "Bob" = "short" } }
+"#;
+
+// one row for each row of transform_test2.csv.gz, so the fields of both sources line up
+const PEOPLE: &str = "\
+name,age
+Alice,34
+Bob,61
+Carol,29
+Dan,45
+Eve,52
+Frank,38
+Grace,27
+Heidi,70
+Ivan,19
+";
+
+#[test]
+fn test_from_config() {
+    let config: DataConfig = toml::from_str(CONFIG).unwrap();
+
+    let compressed_path = PathBuf::from(file!()).parent().unwrap()
+        .join("data/transform_test2.csv.gz");
+    let mut sources: HashMap<String, Box<dyn Read>> = HashMap::new();
+    sources.insert("people".to_string(), Box::new(PEOPLE.as_bytes()));
+    sources.insert("transform_test2.csv.gz".to_string(),
+        Box::new(File::open(compressed_path).unwrap()));
+
+    let df = DataFrame::from_config(&config, sources).unwrap();
+
+    let mut fieldnames = df.fieldnames();
+    fieldnames.sort();
+    assert_eq!(fieldnames, ["age", "f", "name", "name_length_map"]);
+
+    assert_eq!(df.nrows(), 9);
+    assert_eq!(df.get_text_field("name").unwrap()[..3], ["Alice", "Bob", "Carol"]);
+    assert_eq!(df.get_unsigned_field("age").unwrap()[..3], [34, 61, 29]);
+    assert_eq!(df.get_text_field("name_length_map").unwrap()[..3], ["long", "short", "long"]);
+    assert_eq!(df.get_float_field("f").unwrap().len(), 9);
+}
+
+#[test]
+fn test_from_config_missing_source() {
+    let config: DataConfig = toml::from_str(CONFIG).unwrap();
+
+    let mut sources = HashMap::new();
+    sources.insert("people".to_string(), PEOPLE.as_bytes());
+
+    assert!(DataFrame::from_config(&config, sources).is_err());
+}
@@ -0,0 +1,28 @@
+extern crate etl;
+
+use std::path::PathBuf;
+use etl::dataframe::DataFrame;
+
+#[test]
+fn test_glob() {
+    let data_path = PathBuf::from(file!()).parent().unwrap().join("data/glob_test.toml");
+
+    let (config, df) = DataFrame::load(data_path.as_path()).unwrap();
+
+    println!("{:?}", config);
+    println!("{:?}", df);
+
+    let mut fieldnames = df.fieldnames();
+    fieldnames.sort();
+    assert_eq!(fieldnames, ["all_revenue", "region", "revenue", "source_file"]);
+
+    // both source files match the same partitions (including the compressed one), and the
+    // header-only partition sales_2026-10-04.csv contributes no rows
+    assert_eq!(df.nrows(), 5);
+    assert_eq!(df.get_text_field("region").unwrap(), &["US", "CA", "US", "MX", "US"]);
+    assert_eq!(df.get_float_field("revenue").unwrap(), &[10.5, 3.0, 7.25, 1.5, 2.0]);
+    assert_eq!(df.get_text_field("source_file").unwrap(), &["sales_2026-10-01.csv",
+        "sales_2026-10-01.csv", "sales_2026-10-02.csv", "sales_2026-10-03.csv.gz",
+        "sales_2026-10-03.csv.gz"]);
+    assert_eq!(df.get_float_field("all_revenue"), df.get_float_field("revenue"));
+}
@@ -0,0 +1,30 @@
+extern crate etl;
+
+use std::path::PathBuf;
+use etl::dataframe::DataFrame;
+
+#[test]
+fn test_headerless() {
+    let data_path = PathBuf::from(file!()).parent().unwrap().join("data/headerless_test.toml");
+
+    let (config, df) = DataFrame::load(data_path.as_path()).unwrap();
+
+    println!("{:?}", config);
+    println!("{:?}", df);
+
+    let mut fieldnames = df.fieldnames();
+    fieldnames.sort();
+    assert_eq!(fieldnames, ["c", "f"]);
+
+    assert_eq!(df.nrows(), 3);
+    assert_eq!(df.get_signed_field("c").unwrap(), &[1, 4, 5]);
+    assert_eq!(df.get_float_field("f").unwrap(), &[5.0, 8.0, 9.0]);
+}
+
+#[test]
+fn test_headerless_index_out_of_bounds() {
+    let data_path = PathBuf::from(file!()).parent().unwrap()
+        .join("data/headerless_test_bad.toml");
+
+    assert!(DataFrame::load(data_path.as_path()).is_err());
+}
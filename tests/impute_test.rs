@@ -0,0 +1,132 @@
+extern crate etl;
+extern crate toml;
+
+mod common;
+
+use etl::dataframe::DataFrame;
+
+const SOURCE: &str = r#"
+[[source_files]]
+name = "source"
+fields = [ { source_name = "u", field_type = "Unsigned" },
+           { source_name = "s", field_type = "Signed" },
+           { source_name = "f", field_type = "Float" },
+           { source_name = "t", field_type = "Text", null_values = [""] },
+           { source_name = "b", field_type = "Boolean" } ]
+"#;
+
+const DATA: &str = "\
+u,s,f,t,b
+,-4,1.0,,true
+2,,,x,
+3,2,4.0,y,false
+3,-1,,x,false
+10,,2.5,,true
+";
+
+fn load(transforms: &str) -> Option<DataFrame> {
+    common::load(&(SOURCE.to_string() + transforms), DATA).ok()
+}
+
+#[test]
+fn test_impute_constant() {
+    let df = load(r#"
+[[transforms]]
+source_fields = ["u"]
+target_name = "u_const"
+method = { action = "Impute", strategy = "Constant", unsigned = 7, add_indicator = true }
+
+[[transforms]]
+source_fields = ["t"]
+target_name = "t_const"
+method = { action = "Impute", strategy = "Constant", text = "unknown" }
+"#).unwrap();
+
+    assert_eq!(df.get_unsigned_field("u_const").unwrap(), &[7, 2, 3, 3, 10]);
+    assert_eq!(df.get_null_mask("u_const"), None);
+    assert_eq!(df.get_boolean_field("u_const_was_missing").unwrap(),
+        &[true, false, false, false, false]);
+    assert_eq!(df.get_text_field("t_const").unwrap(), &["unknown", "x", "y", "x", "unknown"]);
+
+    assert!(load(r#"
+[[transforms]]
+source_fields = ["u"]
+target_name = "u_const"
+method = { action = "Impute", strategy = "Constant", text = "seven" }
+"#).is_none());
+}
+
+#[test]
+fn test_impute_statistics() {
+    let df = load(r#"
+[[transforms]]
+source_fields = ["f"]
+target_name = "f_mean"
+method = { action = "Impute", strategy = "Mean" }
+
+[[transforms]]
+source_fields = ["f"]
+target_name = "f_median"
+method = { action = "Impute", strategy = "Median" }
+
+[[transforms]]
+source_fields = ["s"]
+target_name = "s_median"
+method = { action = "Impute", strategy = "Median" }
+
+[[transforms]]
+source_fields = ["u"]
+target_name = "u_mean"
+method = { action = "Impute", strategy = "Mean" }
+
+[[transforms]]
+source_fields = ["t"]
+target_name = "t_mode"
+method = { action = "Impute", strategy = "Mode" }
+
+[[transforms]]
+source_fields = ["b"]
+target_name = "b_mode"
+method = { action = "Impute", strategy = "Mode" }
+"#).unwrap();
+
+    assert_eq!(df.get_float_field("f_mean").unwrap(), &[1.0, 2.5, 4.0, 2.5, 2.5]);
+    assert_eq!(df.get_float_field("f_median").unwrap(), &[1.0, 2.5, 4.0, 2.5, 2.5]);
+    assert_eq!(df.get_signed_field("s_median").unwrap(), &[-4, -1, 2, -1, -1]);
+    assert_eq!(df.get_unsigned_field("u_mean").unwrap(), &[5, 2, 3, 3, 10]);
+    assert_eq!(df.get_text_field("t_mode").unwrap(), &["x", "x", "y", "x", "x"]);
+    // ties are broken by first occurrence
+    assert_eq!(df.get_boolean_field("b_mode").unwrap(), &[true, true, false, false, true]);
+
+    // source fields keep their missing values
+    assert_eq!(df.get_null_mask("f"), Some(&vec![false, true, false, true, false]));
+    assert!(df.as_matrix().is_ok());
+
+    assert!(load(r#"
+[[transforms]]
+source_fields = ["t"]
+target_name = "t_mean"
+method = { action = "Impute", strategy = "Mean" }
+"#).is_none());
+}
+
+#[test]
+fn test_impute_forward_fill() {
+    let df = load(r#"
+[[transforms]]
+source_fields = ["s"]
+target_name = "s_ffill"
+method = { action = "Impute", strategy = "ForwardFill", add_indicator = true }
+
+[[transforms]]
+source_fields = ["t"]
+target_name = "t_ffill"
+method = { action = "Impute", strategy = "ForwardFill" }
+"#).unwrap();
+
+    assert_eq!(df.get_signed_field("s_ffill").unwrap(), &[-4, -4, 2, -1, -1]);
+    assert_eq!(df.get_boolean_field("s_ffill_was_missing").unwrap(),
+        &[false, true, false, false, true]);
+    // leading missing values are filled with the first present value
+    assert_eq!(df.get_text_field("t_ffill").unwrap(), &["x", "x", "y", "x", "x"]);
+}
@@ -0,0 +1,87 @@
+extern crate etl;
+extern crate serde_json;
+extern crate toml;
+
+use std::collections::HashMap;
+use std::env;
+use std::fs::{self, File};
+use std::path::Path;
+
+use etl::dataframe::{DataConfig, DataFrame, FieldType};
+
+#[test]
+fn test_infer() {
+    let config = DataConfig::infer(Path::new("tests/data/infer_test.csv"), b',').unwrap();
+    assert_eq!(config.source_files.len(), 1);
+    let source_file = &config.source_files[0];
+    // source files are named relative to a configuration saved alongside them
+    assert_eq!(source_file.name, "infer_test.csv");
+    assert_eq!(source_file.delimiter, None);
+
+    let fields: Vec<(&str, FieldType)> = source_file.fields.iter()
+        .map(|field| (&field.source_name[..], field.field_type)).collect();
+    assert_eq!(fields, [("id", FieldType::Unsigned), ("name", FieldType::Text),
+        ("active", FieldType::Boolean), ("balance", FieldType::Float),
+        ("change", FieldType::Signed), ("joined", FieldType::Date),
+        ("last_login", FieldType::DateTime), ("session", FieldType::Duration),
+        ("notes", FieldType::Text)]);
+
+    // empty strings are missing values of non-text fields by default
+    assert_eq!(source_file.fields[3].null_values, None);
+    assert_eq!(source_file.fields[6].null_values, Some(vec!["NULL".to_string()]));
+    // other missing value tokens aren't taken to be missing values of text fields
+    assert_eq!(source_file.fields[8].null_values, Some(vec!["".to_string()]));
+    assert_eq!(source_file.fields[1].null_values, None);
+}
+
+#[test]
+fn test_infer_roundtrip() {
+    let config = DataConfig::infer(Path::new("tests/data/infer_test.csv"), b',').unwrap();
+
+    let toml_config: DataConfig = toml::from_str(&toml::to_string(&config).unwrap()).unwrap();
+    assert_eq!(toml_config, config);
+    let json_config: DataConfig = serde_json::from_str(&serde_json::to_string(&config).unwrap())
+        .unwrap();
+    assert_eq!(json_config, config);
+
+    // the inferred configuration loads the sampled file
+    let mut sources = HashMap::new();
+    sources.insert(config.source_files[0].name.clone(),
+        File::open("tests/data/infer_test.csv").unwrap());
+    let df = DataFrame::from_config(&config, sources).unwrap();
+    assert_eq!(df.nrows(), 4);
+    assert_eq!(df.null_count("notes"), 2);
+    assert_eq!(df.null_count("last_login"), 1);
+
+    // as does the configuration file, saved next to the sampled file
+    let dir = env::temp_dir().join("etl_infer_roundtrip_test");
+    fs::create_dir_all(&dir).unwrap();
+    fs::copy("tests/data/infer_test.csv", dir.join("infer_test.csv")).unwrap();
+    fs::write(dir.join("config.toml"), toml::to_string(&config).unwrap()).unwrap();
+    assert_eq!(DataFrame::load(&dir.join("config.toml")).unwrap().1.nrows(), 4);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+fn infer_tokens_fields() -> Vec<(String, FieldType, Option<Vec<String>>)> {
+    let config = DataConfig::infer(Path::new("tests/data/infer_tokens_test.csv"), b',').unwrap();
+    config.source_files[0].fields.iter()
+        .map(|field| (field.source_name.clone(), field.field_type, field.null_values.clone()))
+        .collect()
+}
+
+#[test]
+fn test_infer_missing_fields() {
+    // fields without any non-missing values are text
+    let fields = infer_tokens_fields();
+    assert_eq!(fields[1], ("empty".to_string(), FieldType::Text, Some(vec!["".to_string()])));
+}
+
+#[test]
+fn test_infer_text_tokens() {
+    // missing value tokens are kept as values of text fields...
+    let fields = infer_tokens_fields();
+    assert_eq!(fields[0], ("name".to_string(), FieldType::Text, None));
+    // ...but not of other fields
+    assert_eq!(fields[2], ("score".to_string(), FieldType::Unsigned,
+        Some(vec!["None".to_string()])));
+}
@@ -0,0 +1,108 @@
+extern crate etl;
+extern crate wee_matrix as matrix;
+extern crate toml;
+
+mod common;
+
+use std::f64;
+
+use etl::dataframe::{DataConfig, DataFrame, FittedPipeline};
+use matrix::Matrix;
+
+const CONFIG: &str = r#"
+[[source_files]]
+name = "source"
+fields = [ { source_name = "color", field_type = "Text", null_values = [""] },
+           { source_name = "price", field_type = "Float" } ]
+
+[[transforms]]
+source_fields = ["color"]
+target_name = "color"
+method = { action = "VectorizeOneHot", binary_scaling = "NegOneOne" }
+
+[[transforms]]
+source_fields = ["price"]
+target_name = "price_norm"
+method = { action = "Normalize", sample_stdev_correction = 1.0 }
+
+[[transforms]]
+source_fields = ["price"]
+target_name = "price_scaled"
+method = { action = "Scale", min_value = -1.0, max_value = 1.0 }
+
+[[transforms]]
+source_fields = ["color"]
+target_name = "color_code"
+method = { action = "Map", default_value = "?", map = { red = "R", blue = "B" } }
+
+[[transforms]]
+source_fields = ["color"]
+target_name = "color_hashed"
+method = { action = "VectorizeHash", hash_size = 16 }
+"#;
+
+const DATA: &str = "\
+color,price
+red,10
+blue,20
+,30
+";
+
+fn fit() -> (DataConfig, DataFrame, FittedPipeline) {
+    let config = common::config(CONFIG);
+    let (df, fitted) = DataFrame::fit_from_config(&config, common::sources(DATA)).unwrap();
+    (config, df, fitted)
+}
+
+#[test]
+fn test_inverse_roundtrip() {
+    let (config, df, fitted) = fit();
+    let inverse = df.inverse_transform(&config, &fitted, &["price_norm"]).unwrap();
+    assert_eq!(inverse.get_float_field("price").unwrap(), &vec![10.0, 20.0, 30.0]);
+    let inverse = df.inverse_transform(&config, &fitted, &["price_scaled", "color"]).unwrap();
+    assert_eq!(inverse.get_float_field("price").unwrap(), &vec![10.0, 20.0, 30.0]);
+    assert_eq!(inverse.get_text_field("color").unwrap(), &vec!["red", "blue", ""]);
+    assert_eq!(inverse.get_null_mask("color").unwrap(), &vec![false, false, true]);
+    let inverse = df.inverse_transform(&config, &fitted, &["color_code"]).unwrap();
+    assert_eq!(inverse.get_text_field("color").unwrap(), &vec!["red", "blue", ""]);
+    assert_eq!(inverse.null_count("color"), 1);
+}
+
+#[test]
+fn test_inverse_predictions() {
+    let (config, _, fitted) = fit();
+    // e.g. predictions of a model trained on transformed labels
+    let predictions = Matrix::from_vec(vec![0.0, 1.0, f64::NAN, 0.2, -0.3, -1.0, -0.9, 0.4, -1.0],
+        3, 3);
+    let df = DataFrame::from_matrix(&["price_scaled", "color_red", "color_blue"], &predictions)
+        .unwrap();
+    let inverse = df.inverse_transform(&config, &fitted, &["price_scaled", "color"]).unwrap();
+    assert_eq!(inverse.get_float_field("price").unwrap()[..2], [20.0, 30.0]);
+    assert_eq!(inverse.get_null_mask("price").unwrap(), &vec![false, false, true]);
+    // largest value wins, and rows with no value above the "off" value are missing
+    assert_eq!(inverse.get_text_field("color").unwrap()[..2], ["red", "blue"]);
+    assert_eq!(inverse.get_null_mask("color").unwrap(), &vec![false, false, true]);
+
+    assert!(DataFrame::from_matrix(&["price_scaled"], &predictions).is_err());
+}
+
+#[test]
+fn test_inverse_errors() {
+    let (config, df, fitted) = fit();
+    // hashing can't be inverted
+    assert!(df.inverse_transform(&config, &fitted, &["color_hashed"]).is_err());
+    assert!(df.inverse_transform(&config, &fitted, &["no_such_transform"]).is_err());
+    // stateful transforms require fitted parameters
+    assert!(df.inverse_transform(&config, &FittedPipeline::default(), &["price_norm"]).is_err());
+    // generated fields must be present
+    let sub = df.sub(vec!["price_norm"]).unwrap();
+    assert!(sub.inverse_transform(&config, &fitted, &["price_scaled"]).is_err());
+    assert!(sub.inverse_transform(&config, &fitted, &["color"]).is_err());
+
+    // maps must be one-to-one
+    let config: DataConfig = toml::from_str(&CONFIG.replace("blue = \"B\"", "blue = \"R\""))
+        .unwrap();
+    assert!(df.inverse_transform(&config, &fitted, &["color_code"]).is_err());
+    let config: DataConfig = toml::from_str(&CONFIG.replace("\"?\"", "\"B\"")).unwrap();
+    assert!(df.inverse_transform(&config, &fitted, &["color_code"]).is_err());
+}
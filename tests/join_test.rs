@@ -0,0 +1,102 @@
+extern crate etl;
+extern crate toml;
+
+mod common;
+
+use std::path::PathBuf;
+
+use etl::dataframe::{DataFrame, JoinMethod};
+
+use common::frame;
+
+#[test]
+fn test_join_config() {
+    let data_path = PathBuf::from(file!()).parent().unwrap().join("data/join_test.toml");
+
+    let (config, df) = DataFrame::load(data_path.as_path()).unwrap();
+
+    println!("{:?}", config);
+    println!("{:?}", df);
+
+    let mut fieldnames = df.fieldnames();
+    fieldnames.sort();
+    assert_eq!(fieldnames, ["amount", "customer_id", "is_domestic", "name", "order_id"]);
+
+    assert_eq!(df.nrows(), 4);
+    assert_eq!(df.get_unsigned_field("order_id").unwrap(), &[1, 2, 3, 4]);
+    assert_eq!(df.get_text_field("customer_id").unwrap(), &["c2", "c1", "c3", "c2"]);
+    assert_eq!(df.get_text_field("name").unwrap(), &["Bob", "Alice", "", "Bob"]);
+    assert_eq!(df.get_text_field("is_domestic").unwrap(), &["0", "1", "0", "0"]);
+}
+
+const LEFT_CONFIG: &str = r#"
+[[source_files]]
+name = "source"
+fields = [ { source_name = "k1", field_type = "Text" },
+           { source_name = "k2", field_type = "Signed" },
+           { source_name = "x", field_type = "Float" } ]
+"#;
+
+const RIGHT_CONFIG: &str = r#"
+[[source_files]]
+name = "source"
+fields = [ { source_name = "k1", field_type = "Text" },
+           { source_name = "k2", field_type = "Signed" },
+           { source_name = "y", field_type = "Unsigned" } ]
+"#;
+
+#[test]
+fn test_join_api() {
+    let left = frame(LEFT_CONFIG, "k1,k2,x\na,1,0.5\na,2,1.5\nb,1,2.5\n");
+    let right = frame(RIGHT_CONFIG, "k1,k2,y\nb,1,10\na,1,20\na,1,21\nc,3,30\n");
+
+    let inner = left.join(&right, vec!["k1", "k2"], JoinMethod::Inner).unwrap();
+    assert_eq!(inner.fieldnames(), ["k1", "k2", "x", "y"]);
+    assert_eq!(inner.get_text_field("k1").unwrap(), &["a", "a", "b"]);
+    assert_eq!(inner.get_float_field("x").unwrap(), &[0.5, 0.5, 2.5]);
+    assert_eq!(inner.get_unsigned_field("y").unwrap(), &[20, 21, 10]);
+
+    let outer = left.join(&right, vec!["k1", "k2"], JoinMethod::Outer).unwrap();
+    assert_eq!(outer.nrows(), 5);
+    assert_eq!(outer.get_text_field("k1").unwrap(), &["a", "a", "a", "b", "c"]);
+    assert_eq!(outer.get_signed_field("k2").unwrap(), &[1, 1, 2, 1, 3]);
+    assert_eq!(outer.get_unsigned_field("y").unwrap(), &[20, 21, 0, 10, 30]);
+    assert!(outer.get_float_field("x").unwrap()[4].is_nan());
+
+    // key type mismatch
+    assert!(left.join(&right, vec!["k1", "x"], JoinMethod::Inner).is_err());
+}
+
+const TEXT_CONFIG: &str = r#"
+[[source_files]]
+name = "source"
+fields = [ { source_name = "k1", field_type = "Text" },
+           { source_name = "k2", field_type = "Text" },
+           { source_name = "v", field_type = "Unsigned" } ]
+"#;
+
+const FLOAT_CONFIG: &str = r#"
+[[source_files]]
+name = "source"
+fields = [ { source_name = "k", field_type = "Float" },
+           { source_name = "v", field_type = "Unsigned" } ]
+"#;
+
+#[test]
+fn test_join_key_values() {
+    // key values are compared field by field, so text containing separators can't collide
+    let left = frame(TEXT_CONFIG, "k1,k2,v\na\u{1f}b,c,1\na,b,2\n");
+    let right = frame(&TEXT_CONFIG.replace("\"v\"", "\"w\""), "k1,k2,w\na,b\u{1f}c,10\na,b,20\n");
+    let inner = left.join(&right, vec!["k1", "k2"], JoinMethod::Inner).unwrap();
+    assert_eq!(inner.get_unsigned_field("v").unwrap(), &[2]);
+    assert_eq!(inner.get_unsigned_field("w").unwrap(), &[20]);
+
+    // NaN keys never match, and zero keys match regardless of sign
+    let left = frame(FLOAT_CONFIG, "k,v\nNaN,1\n0.0,2\n1.5,3\n");
+    let right = frame(&FLOAT_CONFIG.replace("\"v\"", "\"w\""), "k,w\nNaN,10\n-0.0,20\n1.5,30\n");
+    let inner = left.join(&right, vec!["k"], JoinMethod::Inner).unwrap();
+    assert_eq!(inner.get_unsigned_field("v").unwrap(), &[2, 3]);
+    assert_eq!(inner.get_unsigned_field("w").unwrap(), &[20, 30]);
+    let outer = left.join(&right, vec!["k"], JoinMethod::Outer).unwrap();
+    assert_eq!(outer.nrows(), 4);
+}
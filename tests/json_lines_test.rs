@@ -0,0 +1,24 @@
+extern crate etl;
+
+use std::path::PathBuf;
+use etl::dataframe::DataFrame;
+
+#[test]
+fn test_json_lines() {
+    let data_path = PathBuf::from(file!()).parent().unwrap().join("data/json_lines_test.toml");
+
+    let (config, df) = DataFrame::load(data_path.as_path()).unwrap();
+
+    println!("{:?}", config);
+    println!("{:?}", df);
+
+    let mut fieldnames = df.fieldnames();
+    fieldnames.sort();
+    assert_eq!(fieldnames, ["country", "first_score", "id", "name", "second_score"]);
+
+    assert_eq!(df.get_unsigned_field("id").unwrap(), &[1, 3, 4]);
+    assert_eq!(df.get_text_field("name").unwrap(), &["alice", "carol", "dave"]);
+    assert_eq!(df.get_text_field("country").unwrap(), &["US", "US", "MX"]);
+    assert_eq!(df.get_float_field("second_score").unwrap(), &[0.25, 1.25, 1.75]);
+    assert_eq!(df.get_float_field("first_score").unwrap(), &[0.5, 1.5, 2.5, 3.5]);
+}
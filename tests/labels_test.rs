@@ -0,0 +1,71 @@
+extern crate etl;
+extern crate toml;
+
+mod common;
+
+use etl::dataframe::DataFrame;
+
+use common::sources;
+
+const SOURCE: &str = r#"
+[[source_files]]
+name = "source"
+fields = [ { source_name = "name", field_type = "Text" },
+           { source_name = "rooms", field_type = "Unsigned" },
+           { source_name = "price", field_type = "Float" },
+           { source_name = "sold", field_type = "Boolean" },
+           { source_name = "area", field_type = "Float" } ]
+"#;
+
+const DATA: &str = "\
+name,rooms,price,sold,area
+a,3,250.5,true,80
+b,2,,false,55.5
+c,4,400,true,120
+";
+
+fn load(config: &str) -> Option<DataFrame> {
+    common::load(config, DATA).ok()
+}
+
+#[test]
+fn test_matrix_xy() {
+    let config = common::config(&(SOURCE.to_string() + "\n[labels]\nfields = [\"price\"]\n"));
+    assert_eq!(config.label_fields(), ["price"]);
+    let df = DataFrame::from_config(&config, sources(DATA)).unwrap();
+
+    let (feature_names, x, label_names, y) = df.as_matrix_xy(&config.label_fields()).unwrap();
+    // labels and text fields are excluded from the features
+    assert_eq!(feature_names, ["rooms", "sold", "area"]);
+    assert_eq!(label_names, ["price"]);
+    assert_eq!((x.nrows(), x.ncols()), (3, 3));
+    assert_eq!((y.nrows(), y.ncols()), (3, 1));
+    assert_eq!(x.get(1, 0).unwrap(), 2.0);
+    assert_eq!(x.get(2, 1).unwrap(), 1.0);
+    assert_eq!(x.get(1, 2).unwrap(), 55.5);
+    assert_eq!(y.get(0, 0).unwrap(), 250.5);
+    assert!(y.get(1, 0).unwrap().is_nan());
+
+    // multiple labels are in the order specified
+    let (feature_names, _, label_names, y) = df.as_matrix_xy(&["sold", "price"]).unwrap();
+    assert_eq!(feature_names, ["rooms", "area"]);
+    assert_eq!(label_names, ["sold", "price"]);
+    assert_eq!((y.get(2, 0).unwrap(), y.get(2, 1).unwrap()), (1.0, 400.0));
+}
+
+#[test]
+fn test_label_checks() {
+    let df = load(SOURCE).unwrap();
+    let no_labels: [&str; 0] = [];
+    assert!(df.as_matrix_xy(&no_labels).is_err());
+    assert!(df.as_matrix_xy(&["no_such_field"]).is_err());
+    assert!(df.as_matrix_xy(&["name"]).is_err());
+    assert!(df.as_matrix_xy(&["price", "price"]).is_err());
+
+    // configured labels are checked when loading
+    assert!(load(&(SOURCE.to_string() + "\n[labels]\nfields = [\"rooms\"]\n")).is_some());
+    assert!(load(&(SOURCE.to_string() + "\n[labels]\nfields = [\"name\"]\n")).is_none());
+    assert!(load(&(SOURCE.to_string() + "\n[labels]\nfields = [\"missing\"]\n")).is_none());
+    assert!(load(&(SOURCE.to_string() + "\n[labels]\nfields = []\n")).is_none());
+    assert!(load(&(SOURCE.to_string() + "\n[labels]\nfields = [\"area\", \"area\"]\n")).is_none());
+}
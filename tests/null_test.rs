@@ -0,0 +1,169 @@
+extern crate etl;
+extern crate toml;
+
+mod common;
+
+use etl::dataframe::JoinMethod;
+
+use common::{frame, load};
+
+const CONFIG: &str = r#"
+[[source_files]]
+name = "source"
+fields = [ { source_name = "id", field_type = "Unsigned" },
+           { source_name = "count", field_type = "Signed", null_values = ["", "NULL"] },
+           { source_name = "flag", field_type = "Boolean" },
+           { source_name = "score", field_type = "Float" },
+           { source_name = "name", field_type = "Text", null_values = ["NA"] } ]
+"#;
+
+const DATA: &str = "\
+id,count,flag,score,name
+1,5,true,1.5,alice
+2,NULL,,,NA
+3,,false,3.5,
+4,-2,true,,dave
+";
+
+#[test]
+fn test_null_values() {
+    let df = frame(CONFIG, DATA);
+    assert_eq!(df.nrows(), 4);
+
+    assert_eq!(df.get_unsigned_field("id").unwrap(), &[1, 2, 3, 4]);
+    assert_eq!(df.get_null_mask("id"), None);
+    assert_eq!(df.null_count("id"), 0);
+
+    assert_eq!(df.get_signed_field("count").unwrap(), &[5, 0, 0, -2]);
+    assert_eq!(df.get_null_mask("count").unwrap(), &[false, true, true, false]);
+    assert_eq!(df.get_null_mask("flag").unwrap(), &[false, true, false, false]);
+    assert_eq!(df.get_null_mask("score").unwrap(), &[false, true, false, true]);
+    assert_eq!(df.null_count("score"), 2);
+
+    // empty strings are only missing text values if specified as a null value
+    assert_eq!(df.get_text_field("name").unwrap(), &["alice", "", "", "dave"]);
+    assert_eq!(df.get_null_mask("name").unwrap(), &[false, true, false, false]);
+}
+
+#[test]
+fn test_null_parse_error() {
+    assert!(load(CONFIG, "id,count,flag,score,name\n1,NA,true,1.5,x\n").is_err());
+}
+
+#[test]
+fn test_null_matrix() {
+    let df = frame(CONFIG, DATA);
+    let (fieldnames, matrix) = df.as_matrix().unwrap();
+    assert_eq!(fieldnames, ["id", "count", "flag", "score"]);
+    assert_eq!(matrix.ncols(), 4);
+
+    assert_eq!(matrix.get(0, 1).unwrap(), 5.0);
+    assert!(matrix.get(1, 1).unwrap().is_nan() && matrix.get(2, 1).unwrap().is_nan());
+    assert_eq!(matrix.get(3, 1).unwrap(), -2.0);
+    assert!(matrix.get(1, 2).unwrap().is_nan());
+    assert_eq!(matrix.get(2, 2).unwrap(), 0.0);
+}
+
+#[test]
+fn test_null_filters() {
+    let config = CONFIG.to_string() + r#"
+filters = [ { source_field = "score", filter = { method = "NotNull" } },
+            { source_field = "count", filter = { method = "IsNull" } } ]
+"#;
+    let df = frame(&config, DATA);
+    assert_eq!(df.get_unsigned_field("id").unwrap(), &[3]);
+
+    // missing values never satisfy comparison filters
+    let config = CONFIG.to_string() + r#"
+filters = [ { source_field = "count", filter = { method = "Inequality", inequality = "Lt", signed = 10 } } ]
+"#;
+    let df = frame(&config, DATA);
+    assert_eq!(df.get_unsigned_field("id").unwrap(), &[1, 4]);
+
+    let config = CONFIG.to_string() + r#"
+filters = [ { source_field = "name", filter = { method = "MatchNot", text = "alice" } } ]
+"#;
+    let df = frame(&config, DATA);
+    assert_eq!(df.get_unsigned_field("id").unwrap(), &[3, 4]);
+}
+
+#[test]
+fn test_null_transforms() {
+    let config = CONFIG.to_string() + r#"
+[[transforms]]
+source_fields = ["score"]
+target_name = "score_norm"
+method = { action = "Normalize" }
+
+[[transforms]]
+source_fields = ["name"]
+target_name = "name"
+method = { action = "VectorizeOneHot" }
+
+[[transforms]]
+source_fields = ["count"]
+target_name = "count_float"
+method = { action = "Convert", target_type = "Float" }
+"#;
+    let df = frame(&config, DATA);
+
+    // statistics are computed from the values which are not missing
+    let norm = df.get_float_field("score_norm").unwrap();
+    assert_eq!(norm[0], -1.0);
+    assert_eq!(norm[2], 1.0);
+    assert_eq!(df.get_null_mask("score_norm").unwrap(), &[false, true, false, true]);
+
+    // missing values are not a one-hot category
+    let mut fieldnames = df.fieldnames();
+    fieldnames.retain(|name| name.starts_with("name_"));
+    fieldnames.sort();
+    assert_eq!(fieldnames, ["name_", "name_alice", "name_dave"]);
+    assert_eq!(df.get_float_field("name_alice").unwrap(), &[1.0, 0.0, 0.0, 0.0]);
+    assert_eq!(df.get_float_field("name_").unwrap(), &[0.0, 0.0, 1.0, 0.0]);
+
+    assert_eq!(df.get_null_mask("count_float").unwrap(), &[false, true, true, false]);
+    assert_eq!(df.get_float_field("count_float").unwrap()[3], -2.0);
+}
+
+const CONFIG_AB: &str = r#"
+[[source_files]]
+name = "source"
+fields = [ { source_name = "a", field_type = "Signed" },
+           { source_name = "b", field_type = "Float" } ]
+"#;
+
+const CONFIG_AC: &str = r#"
+[[source_files]]
+name = "source"
+fields = [ { source_name = "a", field_type = "Signed" },
+           { source_name = "c", field_type = "Text" } ]
+"#;
+
+#[test]
+fn test_null_append() {
+    let mut df = frame(CONFIG_AB, "a,b\n1,\n2,2.5\n");
+    df.append_fill_missing(frame(CONFIG_AC, "a,c\n,x\n4,y\n")).unwrap();
+
+    assert_eq!(df.nrows(), 4);
+    assert_eq!(df.get_null_mask("a").unwrap(), &[false, false, true, false]);
+    assert_eq!(df.get_null_mask("b").unwrap(), &[true, false, true, true]);
+    assert_eq!(df.get_null_mask("c").unwrap(), &[true, true, false, false]);
+}
+
+#[test]
+fn test_null_join() {
+    let left = frame(CONFIG_AB, "a,b\n1,1.5\n2,\n,3.5\n");
+    let right = frame(CONFIG_AC, "a,c\n1,x\n3,y\n,z\n");
+
+    // missing keys never match
+    let df = left.join(&right, vec!["a"], JoinMethod::Inner).unwrap();
+    assert_eq!(df.get_signed_field("a").unwrap(), &[1]);
+    assert_eq!(df.get_null_mask("a"), None);
+
+    let df = left.join(&right, vec!["a"], JoinMethod::Outer).unwrap();
+    assert_eq!(df.get_signed_field("a").unwrap(), &[1, 2, 0, 3, 0]);
+    assert_eq!(df.get_null_mask("a").unwrap(), &[false, false, true, false, true]);
+    assert_eq!(df.get_null_mask("b").unwrap(), &[false, true, false, true, true]);
+    assert_eq!(df.get_text_field("c").unwrap(), &["x", "", "", "y", "z"]);
+    assert_eq!(df.get_null_mask("c").unwrap(), &[false, true, true, false, false]);
+}
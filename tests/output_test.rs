@@ -0,0 +1,121 @@
+extern crate etl;
+extern crate toml;
+
+mod common;
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use etl::dataframe::{CsvFormat, DataConfig, DataFrame};
+
+const CONFIG: &str = r#"
+[[source_files]]
+name = "source"
+fields = [ { source_name = "id", field_type = "Unsigned" },
+           { source_name = "name", field_type = "Text" },
+           { source_name = "score", field_type = "Float" },
+           { source_name = "day", field_type = "Date" },
+           { source_name = "took", field_type = "Duration" } ]
+
+[[transforms]]
+source_fields = ["score"]
+target_name = "score_scaled"
+method = { action = "Scale" }
+"#;
+
+const DATA: &str = "\
+id,name,score,day,took
+1,\"smith, al\",1.5,2017-06-30,90
+2,bo,,2017-07-01,01:00:00
+3,cy,3,,0.5
+";
+
+fn frame() -> DataFrame {
+    common::frame(CONFIG, DATA)
+}
+
+fn write(df: &DataFrame, format: &CsvFormat) -> String {
+    let mut written = vec![];
+    df.write_csv(&mut written, format).unwrap();
+    String::from_utf8(written).unwrap()
+}
+
+#[test]
+fn test_write_csv() {
+    let df = frame();
+    assert_eq!(write(&df, &CsvFormat::default()), "\
+id,name,score,day,took,score_scaled
+1,\"smith, al\",1.5,2017-06-30,00:01:30,0
+2,bo,,2017-07-01,01:00:00,
+3,cy,3,,00:00:00.5,1
+");
+
+    let format = CsvFormat {
+        delimiter: b'\t',
+        has_headers: false,
+        float_precision: Some(2),
+        null_value: "NA".to_string(),
+    };
+    assert_eq!(write(&df, &format), "\
+1\tsmith, al\t1.50\t2017-06-30\t00:01:30\t0.00
+2\tbo\tNA\t2017-07-01\t01:00:00\tNA
+3\tcy\t3.00\tNA\t00:00:00.5\t1.00
+");
+}
+
+#[test]
+fn test_write_roundtrip() {
+    // written data can be read back with the same field configuration
+    let df = frame();
+    let written = write(&df, &CsvFormat::default());
+    let reloaded = common::frame(CONFIG, &written);
+    assert_eq!(reloaded.get_text_field("name"), df.get_text_field("name"));
+    assert_eq!(reloaded.get_null_mask("score"), df.get_null_mask("score"));
+    assert_eq!(reloaded.get_date_field("day"), df.get_date_field("day"));
+    assert_eq!(reloaded.get_duration_field("took"), df.get_duration_field("took"));
+}
+
+#[test]
+fn test_write_output() {
+    let dir = env::temp_dir().join("etl_output_test");
+    fs::create_dir_all(&dir).unwrap();
+    fs::copy("tests/data/people.csv", dir.join("people.csv")).unwrap();
+    fs::write(dir.join("config.toml"), r#"
+[[source_files]]
+name = "people.csv"
+delimiter = "\t"
+fields = [ { source_name = "id", field_type = "Text" },
+           { source_name = "age", field_type = "Unsigned" },
+           { source_name = "credit_rating", field_type = "Float" } ]
+
+[output]
+name = "out.tsv"
+delimiter = "\t"
+float_precision = 1
+"#).unwrap();
+
+    let (config, df) = DataFrame::load(&dir.join("config.toml")).unwrap();
+    let output = config.output.unwrap();
+    assert_eq!(Path::new(&output.name), dir.join("out.tsv"));
+    df.write_output(&output).unwrap();
+
+    let written = fs::read_to_string(dir.join("out.tsv")).unwrap();
+    let mut lines = written.lines();
+    assert_eq!(lines.next(), Some("id\tage\tcredit_rating"));
+    assert_eq!(lines.next(), Some("000005457436\t37\t17.0"));
+    assert_eq!(lines.count(), df.nrows() - 1);
+    fs::remove_dir_all(&dir).unwrap();
+
+    // invalid output delimiters are rejected
+    let config: DataConfig = toml::from_str(r#"
+[[source_files]]
+name = "source.csv"
+fields = [ { source_name = "id", field_type = "Text" } ]
+
+[output]
+name = "out.csv"
+delimiter = ";;"
+"#).unwrap();
+    assert!(config.validate().is_err());
+}
@@ -0,0 +1,136 @@
+extern crate etl;
+extern crate toml;
+
+mod common;
+
+use std::borrow::Cow;
+use std::env;
+use std::fs;
+
+use etl::dataframe::{DataConfig, DataFrame};
+
+const CONFIG: &str = r#"
+[[source_files]]
+name = "source"
+fields = [ { source_name = "color", field_type = "Text", add_to_frame = false, null_values = [""] },
+           { source_name = "score", field_type = "Float" },
+           { source_name = "label", field_type = "Unsigned" } ]
+
+[[transforms]]
+source_fields = ["color"]
+target_name = "color"
+method = { action = "VectorizeOneHot" }
+
+[[transforms]]
+source_fields = ["color"]
+target_name = "signed"
+method = { action = "VectorizeOneHot", binary_scaling = "NegOneOne" }
+
+[[transforms]]
+source_fields = ["color"]
+target_name = "hashed"
+method = { action = "VectorizeHash", hash_size = 4096 }
+"#;
+
+const DATA: &str = "\
+color,score,label
+red,0.5,1
+blue,,0
+red,0,1
+,2,0
+";
+
+fn frame() -> DataFrame {
+    common::frame(CONFIG, DATA)
+}
+
+#[test]
+fn test_sparse_matrix() {
+    let df = frame();
+    let (fieldnames, sparse) = df.as_sparse_matrix().unwrap();
+    // hash buckets are kept without dense storage of each bucket
+    assert_eq!(fieldnames.len(), 2 + 2 + 2 + 4096);
+    assert_eq!(&fieldnames[..6], &["score", "label", "color_red", "color_blue", "signed_red",
+        "signed_blue"]);
+    assert_eq!((sparse.nrows, sparse.ncols), (4, fieldnames.len()));
+
+    // zeros are not stored, missing values are stored as NaN, and "off" values of -1 are stored
+    let (indices, values) = sparse.row(1);
+    assert_eq!(&indices[..4], &[0, 3, 4, 5]);
+    assert!(values[0].is_nan());
+    assert_eq!(&values[1..4], &[1.0, -1.0, 1.0]);
+    // each non-missing color sets exactly one hash bucket
+    assert_eq!(indices.len(), 5);
+    assert!(indices[4] >= 6);
+    assert_eq!(sparse.row(3).0, &[0, 4, 5]);
+    assert_eq!(sparse.nnz(), 3 + 2 + 3 + 8 + 3);
+
+    // values match the dense matrix
+    let sub = df.sub(fieldnames[..6].to_vec()).unwrap();
+    let (dense_fieldnames, dense) = sub.as_matrix().unwrap();
+    assert_eq!(&dense_fieldnames[..], &fieldnames[..6]);
+    for row in 0..4 {
+        for col in 0..6 {
+            let (expected, actual) = (dense.get(row, col).unwrap(), sparse.get(row, col));
+            assert!(expected == actual || (expected.is_nan() && actual.is_nan()));
+        }
+    }
+
+    // sparse fields are still available densely (without the dataframe keeping a dense copy)
+    assert_eq!(*df.get_float_values("color_red").unwrap(), [1.0, 0.0, 1.0, 0.0]);
+    assert_eq!(*df.get_float_values("signed_blue").unwrap(), [-1.0, 1.0, -1.0, -1.0]);
+    assert!(matches!(df.get_float_values("color_red").unwrap(), Cow::Owned(_)));
+    assert!(matches!(df.get_float_values("score").unwrap(), Cow::Borrowed(_)));
+    // or as dense fields, which are kept once generated
+    assert_eq!(df.get_float_field("color_red").unwrap(), &vec![1.0, 0.0, 1.0, 0.0]);
+
+    // dataframes with sparse fields can be shared between threads
+    fn is_sync<T: Sync>(_: &T) {}
+    is_sync(&df);
+}
+
+#[test]
+fn test_sparse_append() {
+    let mut df = frame();
+    df.append(frame()).unwrap();
+    assert_eq!(df.nrows(), 8);
+    assert_eq!(*df.get_float_values("color_red").unwrap(),
+        [1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0]);
+    let (_, sparse) = df.as_sparse_matrix().unwrap();
+    assert_eq!(sparse.nnz(), 2 * (3 + 2 + 3 + 8 + 3));
+}
+
+#[test]
+fn test_libsvm() {
+    let df = frame().sub(vec!["label", "score", "color_red", "color_blue"]).unwrap();
+    let mut written = vec![];
+    df.write_libsvm(&mut written, "label").unwrap();
+    assert_eq!(String::from_utf8(written).unwrap(), "\
+1 1:0.5 2:1
+0 3:1
+1 2:1
+0 1:2
+");
+
+    // labels must exist and have no missing values
+    assert!(df.write_libsvm(vec![], "no_such_field").is_err());
+    assert!(df.write_libsvm(vec![], "score").is_err());
+
+    // LibSVM output files are written from the configuration
+    let dir = env::temp_dir().join("etl_libsvm_test");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("source.csv"), DATA).unwrap();
+    fs::write(dir.join("config.toml"), CONFIG.replace("\"source\"", "\"source.csv\"")
+        + "\n[output]\nname = \"out.svm\"\nformat = \"LibSvm\"\nlabel = \"label\"\n").unwrap();
+    let (config, df) = DataFrame::load(&dir.join("config.toml")).unwrap();
+    df.write_output(config.output.as_ref().unwrap()).unwrap();
+    let written = fs::read_to_string(dir.join("out.svm")).unwrap();
+    assert_eq!(written.lines().count(), 4);
+    assert!(written.starts_with("1 1:0.5 2:1 4:1 5:-1 "));
+    fs::remove_dir_all(&dir).unwrap();
+
+    // a label field is required
+    let config: DataConfig = toml::from_str(&(CONFIG.to_string()
+        + "\n[output]\nname = \"out.svm\"\nformat = \"LibSvm\"\n")).unwrap();
+    assert!(config.validate().is_err());
+}
@@ -0,0 +1,46 @@
+extern crate etl;
+#[macro_use] extern crate unittest;
+
+use std::path::PathBuf;
+use etl::dataframe::DataFrame;
+
+#[test]
+fn test_spreadsheet() {
+    let data_path = PathBuf::from(file!()).parent().unwrap().join("data/spreadsheet_test.toml");
+
+    let (config, df) = DataFrame::load(data_path.as_path()).unwrap();
+
+    println!("{:?}", config);
+    println!("{:?}", df);
+
+    let mut fieldnames = df.fieldnames();
+    fieldnames.sort();
+    assert_eq!(fieldnames, ["age", "effective_tax", "gender_code", "id", "income"]);
+
+    let gender_code = df.get_text_field("gender_code").unwrap();
+    assert!(!gender_code.is_empty());
+    assert!(gender_code.iter().all(|g| g == "F"));
+
+    let ids = df.get_text_field("id").unwrap();
+    assert_eq!(ids[0], "000004841186");
+    assert_eq!(df.get_unsigned_field("age").unwrap()[0], 61);
+    assert_eq!(df.get_unsigned_field("income").unwrap()[0], 216035);
+    assert_fpvec_eq!(df.get_float_field("effective_tax").unwrap()[..1], [0.1510380668], 1e-9);
+}
+
+#[test]
+fn test_spreadsheet_headerless() {
+    let data_path = PathBuf::from(file!()).parent().unwrap()
+        .join("data/spreadsheet_regression_test.toml");
+
+    let (_, df) = DataFrame::load(data_path.as_path()).unwrap();
+
+    let mut fieldnames = df.fieldnames();
+    fieldnames.sort();
+    assert_eq!(fieldnames, ["x", "x_scaled"]);
+
+    let x = df.get_float_field("x").unwrap();
+    assert_eq!(x.len(), 100);
+    assert_fpvec_eq!(x[..3], [0.8400212339, 0.6222449985, 0.2730513363], 1e-9);
+    assert_eq!(df.get_float_field("x_scaled").unwrap().len(), 100);
+}
@@ -0,0 +1,125 @@
+extern crate etl;
+extern crate serde_json;
+extern crate toml;
+
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use etl::dataframe::{DataConfig, DataFrame, SourceFormat};
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(file!()).parent().unwrap().join("data").join(name)
+}
+
+fn frame() -> DataFrame {
+    DataFrame::load(&fixture("roundtrip_test.toml")).unwrap().1
+}
+
+/// Configuration for querying the fields of the round-trip test frame from a SQLite database
+fn written_config(name: &str, query: Option<&str>) -> DataConfig {
+    let mut config: DataConfig = toml::from_str(
+        &fs::read_to_string(fixture("roundtrip_written.toml")).unwrap()).unwrap();
+    config.source_files[0].name = name.to_string();
+    config.source_files[0].format = Some(SourceFormat::Sqlite);
+    config.source_files[0].query = query.map(|query| query.to_string());
+    config
+}
+
+fn test_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn reload(dir: &Path, query: &str) -> Option<DataFrame> {
+    fs::write(dir.join("reload.json"),
+        serde_json::to_string(&written_config("out.sqlite", Some(query))).unwrap()).unwrap();
+    DataFrame::load(&dir.join("reload.json")).ok().map(|(_, df)| df)
+}
+
+#[test]
+fn test_sqlite_roundtrip() {
+    let dir = test_dir("etl_sqlite_roundtrip_test");
+    let df = frame();
+    df.write_sqlite(&dir.join("out.sqlite"), "people").unwrap();
+    // tables can't be overwritten
+    assert!(df.write_sqlite(&dir.join("out.sqlite"), "people").is_err());
+
+    let reloaded = reload(&dir, "SELECT * FROM people ORDER BY id").unwrap();
+    assert_eq!(reloaded.nrows(), 3);
+    assert_eq!(reloaded.get_unsigned_field("id"), df.get_unsigned_field("id"));
+    assert_eq!(reloaded.get_signed_field("delta"), df.get_signed_field("delta"));
+    assert_eq!(reloaded.get_text_field("name"), df.get_text_field("name"));
+    assert_eq!(reloaded.get_boolean_field("flag"), df.get_boolean_field("flag"));
+    // missing floating-point values are NaN placeholders, so only compare present values
+    let score = reloaded.get_float_field("score").unwrap();
+    assert_eq!((score[0], score[2]), (1.5, 3.0));
+    assert_eq!(reloaded.get_date_field("day"), df.get_date_field("day"));
+    assert_eq!(reloaded.get_datetime_field("at"), df.get_datetime_field("at"));
+    assert_eq!(reloaded.get_duration_field("took"), df.get_duration_field("took"));
+    for field_name in df.fieldnames() {
+        assert_eq!(reloaded.get_null_mask(field_name), df.get_null_mask(field_name));
+    }
+
+    // queries can filter, and compute result columns
+    let reloaded = reload(&dir,
+        "SELECT id, delta * 10 AS delta, name, flag, score, day, at, took, name_al, name_cy \
+         FROM people WHERE delta IS NOT NULL ORDER BY id DESC").unwrap();
+    assert_eq!(reloaded.get_unsigned_field("id").unwrap(), &vec![3, 1]);
+    assert_eq!(reloaded.get_signed_field("delta").unwrap(), &vec![70, -20]);
+    assert_eq!(reloaded.get_boolean_field("flag").unwrap(), &vec![false, true]);
+
+    // missing result columns and invalid queries are reported
+    assert!(reload(&dir, "SELECT id FROM people").is_none());
+    assert!(reload(&dir, "SELECT * FROM no_such_table").is_none());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_sqlite_no_fields() {
+    let dir = test_dir("etl_sqlite_no_fields_test");
+    let mut config: DataConfig = toml::from_str(
+        &fs::read_to_string(fixture("roundtrip_test.toml")).unwrap()).unwrap();
+    config.transforms = None;
+    for field in config.source_files[0].fields.iter_mut() {
+        field.add_to_frame = Some(false);
+    }
+    let mut sources = HashMap::new();
+    sources.insert(config.source_files[0].name.clone(),
+        fs::File::open(fixture("roundtrip_test.csv")).unwrap());
+    let df = DataFrame::from_config(&config, sources).unwrap();
+    assert!(df.fieldnames().is_empty());
+
+    // a table needs at least one column, so frames without fields are rejected up front
+    assert!(df.write_sqlite(&dir.join("out.sqlite"), "people").is_err());
+    assert!(!dir.join("out.sqlite").exists());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_sqlite_config() {
+    let dir = test_dir("etl_sqlite_config_test");
+    fs::copy(fixture("roundtrip_test.csv"), dir.join("roundtrip_test.csv")).unwrap();
+    fs::write(dir.join("config.toml"), fs::read_to_string(fixture("roundtrip_test.toml")).unwrap()
+        + "\n[output]\nname = \"out.sqlite\"\nformat = \"Sqlite\"\ntable = \"people\"\n")
+        .unwrap();
+    let (config, df) = DataFrame::load(&dir.join("config.toml")).unwrap();
+    df.write_output(config.output.as_ref().unwrap()).unwrap();
+    assert_eq!(reload(&dir, "SELECT * FROM people").unwrap().nrows(), 3);
+
+    // SQLite sources require a query, and SQLite outputs require a table
+    assert!(written_config("out.sqlite", None).validate().is_err());
+    let config: DataConfig = toml::from_str(&(fs::read_to_string(fixture("roundtrip_test.toml"))
+        .unwrap() + "\n[output]\nname = \"out.sqlite\"\nformat = \"Sqlite\"\n")).unwrap();
+    assert!(config.validate().is_err());
+
+    // SQLite sources can also be provided by readers
+    let config = written_config("db", Some("SELECT * FROM people"));
+    let mut sources = HashMap::new();
+    sources.insert("db".to_string(), fs::File::open(dir.join("out.sqlite")).unwrap());
+    assert_eq!(DataFrame::from_config(&config, sources).unwrap().nrows(), 3);
+    fs::remove_dir_all(&dir).unwrap();
+}
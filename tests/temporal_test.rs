@@ -0,0 +1,204 @@
+extern crate etl;
+extern crate chrono;
+extern crate toml;
+
+mod common;
+
+use chrono::{Duration, NaiveDate};
+
+use etl::dataframe::DataFrame;
+
+const SOURCE: &str = r#"
+[[source_files]]
+name = "source"
+fields = [ { source_name = "day", field_type = "Date", format = "%d/%m/%Y" },
+           { source_name = "at", field_type = "DateTime" },
+           { source_name = "took", field_type = "Duration" } ]
+"#;
+
+const DATA: &str = "\
+day,at,took
+30/06/2017,2017-06-30T14:05:00,01:30:00
+01/07/2017,2017-07-01 08:00:00.5,90
+,2017-07-02T10:00:00+02:00,-00:00:01.25
+";
+
+fn load(config: &str) -> Option<DataFrame> {
+    common::load(config, DATA).ok()
+}
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+#[test]
+fn test_temporal_fields() {
+    let df = load(SOURCE).unwrap();
+    assert_eq!(df.nrows(), 3);
+
+    assert_eq!(df.get_date_field("day").unwrap()[..2], [date(2017, 6, 30), date(2017, 7, 1)]);
+    assert_eq!(df.get_null_mask("day").unwrap(), &[false, false, true]);
+    // values with a UTC offset are converted to UTC
+    assert_eq!(df.get_datetime_field("at").unwrap(), &[
+        date(2017, 6, 30).and_hms_opt(14, 5, 0).unwrap(),
+        date(2017, 7, 1).and_hms_milli_opt(8, 0, 0, 500).unwrap(),
+        date(2017, 7, 2).and_hms_opt(8, 0, 0).unwrap()]);
+    assert_eq!(df.get_duration_field("took").unwrap(), &[Duration::minutes(90),
+        Duration::seconds(90), -Duration::milliseconds(1250)]);
+
+    // dates which don't match the field format fail to load
+    assert!(load(&SOURCE.replace("%d/%m/%Y", "%Y-%m-%d")).is_none());
+}
+
+#[test]
+fn test_temporal_matrix() {
+    let df = load(SOURCE).unwrap();
+    let (fieldnames, matrix) = df.as_matrix().unwrap();
+    assert_eq!(fieldnames, ["day", "at", "took"]);
+
+    assert_eq!(matrix.get(0, 0).unwrap(), 1498780800.0);
+    assert!(matrix.get(2, 0).unwrap().is_nan());
+    assert_eq!(matrix.get(0, 1).unwrap(), 1498831500.0);
+    assert_eq!(matrix.get(1, 1).unwrap(), 1498896000.5);
+    assert_eq!(matrix.get(0, 2).unwrap(), 5400.0);
+    assert_eq!(matrix.get(2, 2).unwrap(), -1.25);
+}
+
+#[test]
+fn test_temporal_filters() {
+    let df = load(&(SOURCE.to_string() + r#"
+filters = [ { source_field = "day", filter = { method = "Inequality", inequality = "Gte", date = "2017-07-01" } } ]
+"#)).unwrap();
+    assert_eq!(df.get_date_field("day").unwrap(), &[date(2017, 7, 1)]);
+
+    let df = load(&(SOURCE.to_string() + r#"
+filters = [ { source_field = "at", filter = { method = "Inequality", inequality = "Lt", datetime = "2017-07-01T12:00:00" } } ]
+"#)).unwrap();
+    assert_eq!(df.nrows(), 2);
+
+    let df = load(&(SOURCE.to_string() + r#"
+filters = [ { source_field = "took", filter = { method = "Inequality", inequality = "Gt", duration = "00:01:00" } } ]
+"#)).unwrap();
+    assert_eq!(df.get_duration_field("took").unwrap(), &[Duration::minutes(90),
+        Duration::seconds(90)]);
+}
+
+#[test]
+fn test_temporal_convert() {
+    let df = load(&(SOURCE.to_string() + r#"
+[[transforms]]
+source_fields = ["at"]
+target_name = "at_date"
+method = { action = "Convert", target_type = "Date" }
+
+[[transforms]]
+source_fields = ["day"]
+target_name = "day_text"
+method = { action = "Convert", target_type = "Text", format = "%B %-d, %Y" }
+
+[[transforms]]
+source_fields = ["day_text"]
+target_name = "day_parsed"
+method = { action = "Convert", target_type = "Date", format = "%B %-d, %Y" }
+
+[[transforms]]
+source_fields = ["at"]
+target_name = "at_epoch"
+method = { action = "Convert", target_type = "Signed" }
+
+[[transforms]]
+source_fields = ["at_epoch"]
+target_name = "at_roundtrip"
+method = { action = "Convert", target_type = "DateTime" }
+
+[[transforms]]
+source_fields = ["took"]
+target_name = "took_text"
+method = { action = "Convert", target_type = "Text" }
+"#)).unwrap();
+
+    assert_eq!(df.get_date_field("at_date").unwrap(), &[date(2017, 6, 30), date(2017, 7, 1),
+        date(2017, 7, 2)]);
+    assert_eq!(df.get_text_field("day_text").unwrap()[..2], ["June 30, 2017", "July 1, 2017"]);
+    assert_eq!(df.get_null_mask("day_text").unwrap(), &[false, false, true]);
+    assert_eq!(df.get_date_field("day_parsed").unwrap()[..2], [date(2017, 6, 30),
+        date(2017, 7, 1)]);
+    assert_eq!(df.get_null_mask("day_parsed").unwrap(), &[false, false, true]);
+    assert_eq!(df.get_signed_field("at_epoch").unwrap(), &[1498831500, 1498896000, 1498982400]);
+    assert_eq!(df.get_datetime_field("at_roundtrip").unwrap()[0],
+        date(2017, 6, 30).and_hms_opt(14, 5, 0).unwrap());
+    assert_eq!(df.get_text_field("took_text").unwrap(), &["01:30:00", "00:01:30",
+        "-00:00:01.25"]);
+
+    // conversions between booleans and dates are unsupported
+    assert!(load(&(SOURCE.to_string() + r#"
+[[transforms]]
+source_fields = ["day"]
+target_name = "day_bool"
+method = { action = "Convert", target_type = "Boolean" }
+"#)).is_none());
+}
+
+#[test]
+fn test_date_parts() {
+    let df = load(&(SOURCE.to_string() + r#"
+[[transforms]]
+source_fields = ["day"]
+target_name = "day"
+method = { action = "DateParts" }
+
+[[transforms]]
+source_fields = ["at"]
+target_name = "at"
+method = { action = "DateParts", parts = ["Hour", "IsWeekend", "MonthSin", "MonthCos", "HourCos"] }
+"#)).unwrap();
+
+    assert_eq!(df.get_signed_field("day_year").unwrap()[..2], [2017, 2017]);
+    assert_eq!(df.get_unsigned_field("day_month").unwrap()[..2], [6, 7]);
+    assert_eq!(df.get_unsigned_field("day_day").unwrap()[..2], [30, 1]);
+    // Friday and Saturday, with days numbered from Monday
+    assert_eq!(df.get_unsigned_field("day_day_of_week").unwrap()[..2], [4, 5]);
+    assert_eq!(df.get_null_mask("day_day_of_week").unwrap(), &[false, false, true]);
+
+    assert_eq!(df.get_unsigned_field("at_hour").unwrap(), &[14, 8, 8]);
+    assert_eq!(df.get_boolean_field("at_is_weekend").unwrap(), &[false, true, true]);
+    let month_sin = df.get_float_field("at_month_sin").unwrap();
+    let month_cos = df.get_float_field("at_month_cos").unwrap();
+    assert!((month_sin[0] - 0.5).abs() < 1e-12 && (month_cos[0] + 0.75f64.sqrt()).abs() < 1e-12);
+    assert!(month_sin[1].abs() < 1e-12 && (month_cos[1] + 1.0).abs() < 1e-12);
+    assert!((df.get_float_field("at_hour_cos").unwrap()[1] + 0.5).abs() < 1e-12);
+    assert!(df.get_unsigned_field("at_year").is_none());
+
+    // time parts can't be extracted from dates
+    assert!(load(&(SOURCE.to_string() + r#"
+[[transforms]]
+source_fields = ["day"]
+target_name = "day"
+method = { action = "DateParts", parts = ["Hour"] }
+"#)).is_none());
+}
+
+#[test]
+fn test_duration_range() {
+    let load_values = |field_type: &str, value: &str, transform: bool| {
+        let mut config = format!("[[source_files]]\nname = \"source\"\n\
+            fields = [ {{ source_name = \"value\", field_type = \"{}\" }} ]\n", field_type);
+        if transform {
+            config += "\n[[transforms]]\nsource_fields = [\"value\"]\ntarget_name = \"took\"\n\
+                method = { action = \"Convert\", target_type = \"Duration\" }\n";
+        }
+        common::load(&config, &format!("value\n{}\n", value)).map(|df| df.get_duration_field(
+            if transform { "took" } else { "value" }).unwrap()[0])
+    };
+    assert_eq!(load_values("Duration", "100:00:00", false).unwrap(), Duration::hours(100));
+    assert_eq!(load_values("Float", "1.5", true).unwrap(), Duration::milliseconds(1500));
+    assert_eq!(load_values("Unsigned", "60", true).unwrap(), Duration::minutes(1));
+    assert_eq!(load_values("Signed", "-60", true).unwrap(), Duration::minutes(-1));
+
+    // durations which are out of range are errors
+    assert!(load_values("Duration", "9999999999999999999:00:00", false).is_err());
+    assert!(load_values("Duration", "1e18", false).is_err());
+    assert!(load_values("Float", "1e18", true).is_err());
+    assert!(load_values("Unsigned", "18446744073709551615", true).is_err());
+    assert!(load_values("Signed", "-9223372036854775808", true).is_err());
+}